@@ -12,3 +12,10 @@ until-empty = bis leer
 power-settings = Energie- und Akkueinstellungen...
 dgpu-running = Dedizierte GPU ist aktiv und kann die Akkulaufzeit reduzieren
 dgpu-applications = Anwendungen, die die dedizierte GPU {$gpu_name} nutzen
+time-less-than-5-minutes = weniger als 5 Minuten
+time-about-minutes = etwa {$minutes} Minuten
+time-about-an-hour = etwa eine Stunde
+time-about-hours = etwa {$hours} Stunden
+time-about-a-day = etwa ein Tag
+time-about-a-day-and-a-half = etwa anderthalb Tage
+time-about-days = etwa {$days} Tage
@@ -1,14 +1,205 @@
 battery = Battery
+no-battery = No battery, running on AC power
+upower-unreachable = Battery status is unavailable: {$error}
 battery-desc = Reduced power usage and performance.
 balanced = Balanced
 balanced-desc = Standard performance and battery usage.
 performance = High Performance
 performance-desc = High performance and power usage.
-max-charge = Increase the lifespan of your battery by setting a maximum charge value of 80%
+profile-saves = saves ~{$watts} W
+profile-costs = +{$watts} W
+profile-calibrating = calibrating…
+max-charge = Increase the lifespan of your battery by setting a maximum charge value of {$percent}%
+charge-limit-threshold = Maximum charge
 seconds = s
 minutes = m
 hours = h
 until-empty = until empty
+fully-charged = Fully charged
 power-settings = Power & Battery settings...
+power-settings-failed = Couldn't open the settings.
 dgpu-running = Discrete GPU is active and can reduce battery life
 dgpu-applications = Applications using {$gpu_name} discrete GPU
+suspend-mode = Suspend mode
+suspend-mode-desc = Changes apply on the next suspend.
+suspend-s2idle = Modern standby (S0ix)
+suspend-s2idle-desc = Faster resume, about 0.5 W while asleep. Requires firmware support.
+suspend-shallow = Standby (S1)
+suspend-shallow-desc = Light sleep with higher power usage.
+suspend-deep = Suspend to RAM (S3)
+suspend-deep-desc = Slower resume, about 0.1 W while asleep.
+hibernate-in = Will hibernate in { $time } if not resumed
+hibernate-on-low-battery = Will hibernate when the battery runs low if not resumed
+resume-report = Resume report
+woke-from = Woke from: { $sources }
+wake-drain = { $device } kept the system awake and may be draining power
+wake-usb = USB
+wake-network = Network
+wake-timer = Timer
+wake-lid = Lid
+wake-power-button = Power button
+wake-other = Other
+power-devices = Power devices
+upower-devices = Reported by UPower
+kernel-params = Show kernel power parameters
+kernel-params-none = No GPU driver power parameters found.
+kernel-param-rc6 = Lets the GPU enter the RC6 sleep state when idle. -1 picks the driver default.
+kernel-param-psr = Panel Self Refresh: the display keeps showing a still image while the GPU sleeps.
+kernel-param-fbc = Framebuffer compression, which reduces memory traffic to the display.
+kernel-param-dc = Display C-states, deeper power states for the display engine when idle.
+kernel-param-runpm = Runtime power management: powers the GPU down when nothing uses it.
+kernel-param-aspm = PCIe Active State Power Management for the link to the GPU.
+kernel-param-dpm = Dynamic power management, which scales GPU clocks with load.
+kernel-param-abmlevel = Adaptive backlight management: lowers the backlight and raises contrast to compensate. 0 is off.
+wake-on-lan = Wake on LAN
+usb-power = USB power
+usb-power-auto = Suspends when idle
+usb-power-on = Always on
+usb-power-saved = About { $watts } W saved with { $count } devices suspending when idle
+power-quality = Power quality
+power-quality-ups = UPS: {$name}
+power-quality-voltage = Input voltage: {$voltage}
+power-quality-frequency = Input frequency: {$frequency}
+power-quality-unknown = Not reported
+storage-mode = Storage mode
+storage-mode-desc = Hold the battery between 40% and 50% while the device is stored for weeks or months.
+storage-mode-active = STORAGE MODE ACTIVE
+storage-mode-active-desc = The battery will not charge above 50%. Disable storage mode before regular use.
+storage-mode-disable = Disable storage mode
+calibration-recommended = Battery calibration
+calibration-recommended-desc = Calibrate your battery for accurate time estimates.
+calibration-start = Start calibration
+calibration-dismiss = Not now
+calibration-running = Calibrating battery
+calibration-discharge = Step 1 of 2: unplug and let the battery run down
+calibration-charge = Step 2 of 2: plug in and charge to full
+calibration-cancel = Cancel calibration
+fan = Fan {$number}
+fan-rpm = {$rpm} RPM
+export-csv = Export power data to CSV...
+export-csv-title = Export power data
+what-if = What if…
+what-if-remaining = At {$watts} W: {$time} remaining
+what-if-unknown = Remaining time unknown
+low-battery = Battery low
+very-low-battery = Battery very low — plug in soon
+deep-discharge = Battery critically low — save your work now
+critical-battery = Battery almost empty
+battery-remaining = {$percent} remaining
+unplugged = Switched to battery
+unplugged-remaining = {$percent} remaining, approximately {$time}
+plugged-in = Plugged in
+plugged-in-charge = {$percent} charged
+on-battery-power = On battery power
+plugged-in-charging = Plugged in — charging
+plugged-in-not-charging = Plugged in — not charging
+docked-protection = Limit charging to {$percent}% while docked, and lift the limit when unplugged below 20%
+scheduled-shutdown = Scheduled shutdown
+scheduled-shutdown-desc = The system will power off in {$minutes} minutes.
+scheduled-shutdown-in = Scheduled shutdown in {$minutes} min
+cancel-shutdown = Cancel Shutdown
+health-good = Good
+health-fair = Fair
+health-poor = Poor — consider battery replacement
+similar-hardware = Battery life vs. similar hardware: you {$yours}, typical {$typical} ({$percent}%)
+health-tooltip = System health: battery {$battery}% (50%), thermal {$thermal}% (30%), uptime {$uptime}% (20%)
+time-less-than-5-minutes = less than 5 minutes
+time-about-minutes = about {$minutes} minutes
+time-about-an-hour = about an hour
+time-about-hours = about {$hours} hours
+time-about-a-day = about a day
+time-about-a-day-and-a-half = about a day and a half
+time-about-days = about {$days} days
+energy-used = Energy used since boot: {$energy} Wh
+energy-wall = Drawn from the wall for charging: {$energy} Wh (est. 85% efficiency)
+screen-off-drain = Idle (screen off): {$watts} W
+active-drain = In use: {$watts} W
+high-idle-drain = High idle drain detected — check connected USB devices
+time-less-than-a-minute = Less than a minute
+time-minutes = {$minutes} min
+time-hours = {$hours} hr
+time-hours-minutes = {$hours} hr {$minutes} min
+time-until-full = {$time} until full
+time-until-empty = {$time} until empty
+time-estimating = Estimating time remaining…
+time-margin = ±{$minutes} min
+time-varies = Varies significantly
+icon-theme = Battery icons
+icon-theme-system = System default
+cpu-governor = CPU governor: {$governor}
+screen-timeout = Screen timeout
+never = Never
+suspend-idle = Suspend when inactive
+suspend-idle-desc = 0 minutes never suspends.
+suspend-idle-ac = On AC power
+suspend-idle-battery = On battery
+minutes-value = {$minutes} min
+charge-limit-suggestion = Recommended: set charging limit to {$limit}% for your battery chemistry
+charge-limit-suggestion-desc = Charging to a lower level slows battery wear.
+charge-limit-suggestion-accept = Set limit
+charge-limit-suggestion-dismiss = Don't ask again
+thermal-mitigation = Switched to Power Saver to cool down.
+thermal-mitigation-desc = The CPU has been running hot. The previous power profile comes back once it cools down.
+trip-reminder = You have a trip tomorrow
+trip-reminder-desc = Charging is limited to {$percent}%. Consider charging to 100%?
+trip-charge-full = Charge to 100% now
+trip-remind-later = Remind me later
+battery-firmware = Firmware: {$version}
+battery-health = Battery health: {$percent}% of design capacity
+battery-health-suggestion = Turn on the charging limit to slow further wear.
+battery-voltage = Voltage: {$voltage} V
+battery-voltage-low = The voltage is well below the design minimum, which may indicate a failing battery.
+device-battery = Battery
+device-ups = UPS
+device-mouse = Mouse
+device-keyboard = Keyboard
+device-tablet = Tablet
+device-phone = Phone
+device-gaming-input = Game controller
+device-headset = Headset
+device-speakers = Speakers
+device-headphones = Headphones
+device-charging = Charging
+device-discharging = Discharging
+smart-sleep = Suspend when a long break seems likely
+smart-sleep-pending = Going to sleep soon?
+smart-sleep-pending-desc = Move the mouse or type within a minute to stay awake.
+smart-sleep-cancel = Stay awake
+keep-awake = Keep the screen awake
+link-displays = Link display brightness
+night-light = Night light
+night-light-automatic = From sunset to sunrise
+keep-awake-reason = Keeping the screen awake from the battery applet
+dock-remember = Use Performance with {$name}
+dock-remember-desc = Switches to Performance at full brightness while this dock is connected.
+dock-forget = Forget dock
+dock-remembered = Performance while {$name} is connected
+dock-disconnected = the dock
+modem-status = Modem: {$technology} {$state}, {$bars ->
+    [one] 1 bar
+   *[other] {$bars} bars
+}
+modem-status-plain = Modem: {$state}
+modem-disabled = disabled
+modem-searching = searching
+modem-registered = registered
+modem-connected = connected
+modem-disable = Disable modem
+modem-disable-desc = An active modem adds about 1–3 W of power draw.
+modem-enable = Enable modem
+modem-enable-desc = Reconnects to the mobile network.
+energy-goal = {$used} of {$budget} Wh today
+energy-goal-nearing = Most of today's energy budget is used
+energy-goal-nearing-desc = You've used {$used}/{$budget} Wh today — consider closing unused tabs.
+unusual-drain = Unusual power drain detected (currently {$current} W vs. typical {$typical} W)
+swipe-brightness = Brightness {$percent}%
+plug-in-reminder = Battery low — plug in your charger
+suspend = Suspend
+hibernate = Hibernate
+power-off = Power off
+power-off-confirm = Power off the computer? Unsaved work in all sessions will be lost.
+restart = Restart
+restart-confirm = Restart the computer? Unsaved work in all sessions will be lost.
+cancel = Cancel
+lock-screen = Lock screen
+show-all-options = Show all options
@@ -12,3 +12,10 @@ until-empty = hasta que se agote
 power-settings = Configuración de alimentación y batería...
 dgpu-running = La tarjeta gráfica externa está activada y puede reducir la carga restante
 dgpu-applications = Programas utilizando {$gpu_name} la tarjeta gráfica externa
+time-less-than-5-minutes = menos de 5 minutos
+time-about-minutes = unos {$minutes} minutos
+time-about-an-hour = alrededor de una hora
+time-about-hours = unas {$hours} horas
+time-about-a-day = alrededor de un día
+time-about-a-day-and-a-half = alrededor de un día y medio
+time-about-days = unos {$days} días
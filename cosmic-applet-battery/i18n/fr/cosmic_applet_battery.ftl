@@ -12,3 +12,10 @@ until-empty = avant la décharge totale
 power-settings = Paramètres d'alimentation...
 dgpu-running = Le GPU dédié est actif et peu réduire l'autonomie de la batterie
 dgpu-applications = Applications utilisant le GPU dédié {$gpu_name}
+time-less-than-5-minutes = moins de 5 minutes
+time-about-minutes = environ {$minutes} minutes
+time-about-an-hour = environ une heure
+time-about-hours = environ {$hours} heures
+time-about-a-day = environ un jour
+time-about-a-day-and-a-half = environ un jour et demi
+time-about-days = environ {$days} jours
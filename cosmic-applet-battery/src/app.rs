@@ -1,41 +1,100 @@
 // Copyright 2023 System76 <info@system76.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+#[cfg(feature = "energy-goals")]
+use crate::energy_goal::{self, EnergyGoal};
 use crate::{
     backend::{
-        Power, PowerProfileRequest, PowerProfileUpdate, get_charging_limit,
-        power_profile_subscription, set_charging_limit,
+        Power, PowerProfileRequest, PowerProfileUpdate,
+        backlight::{
+            Backlight, backlight, backlight_subscription, has_kbd_backlight, set_brightness,
+        },
+        calendar::trip_tomorrow,
+        clear_charging_limit,
+        cpufreq::{CpuGovernor, get_governor, set_governor},
+        ddc::{self, ExternalDisplay},
+        device::{
+            BatteryDetails, BatteryDevice, DeviceDbusEvent, PowerDevice, RecordedCharge,
+            battery_details, battery_devices_subscription, charge_history, device_subscription,
+            power_devices, sample_energy_rate,
+        },
+        dock::{ThunderboltDevice, is_docked, thunderbolt_subscription},
+        enable_storage_mode,
+        gamma::{self, GammaControl},
+        get_charging_limit,
+        hwmon::{Fan, cpu_temperature_subscription, fan_subscription},
+        idle::{IDLE_CONFIG_ID, IdleConfig, SCREEN_TIMEOUT_PRESETS, SuspendDelays, idle_config},
+        idle_notify::idle_subscription,
+        inhibit::{Inhibitor, inhibit_idle},
+        kernel_params::{KernelParam, kernel_params},
+        logind::{
+            HibernateAfter, PRIMARY_SEAT, PowerActions, hibernate, power_actions, power_off,
+            reboot, sleep_subscription, suspend, suspend_then_hibernate,
+        },
+        mem_sleep::{MemSleep, MemSleepState, get_mem_sleep, set_mem_sleep},
+        modem::{ModemState, ModemStatus, modem_status, set_modem_enabled},
+        nut::{PowerQuality, power_quality},
+        power_profile_subscription,
+        screensaver::{can_lock, lock, screensaver_subscription},
+        set_charge_thresholds, set_charging_limit,
+        upower_device::{BatteryState, DeviceKind, WarningLevel},
+        usb::{self, UsbDevice},
+        wake_on_lan::{WakeOnLan, set_wake_on_lan, wake_on_lan},
+        wakeup::{ResumeSource, WakeKind, WakeupSource, resume_report, wakeup_sources},
+    },
+    battery_ring::battery_ring,
+    battery_widget::{BatteryWidget, BatteryWidgetMsg},
+    chemistry::{self, Chemistry},
+    config::{
+        self, BatteryConfig, CalibrationStep, ChemistrySuggestion, PanelDisplay, ScrollAction,
+        SwipeAction, TrayMode,
     },
-    config,
     dgpu::{Entry, GpuUpdate, dgpu_subscription},
+    discharge_chart,
+    display_server::DisplayServer,
     fl,
+    format::{self, duration_to_natural},
+    gesture_area::{GestureArea, SwipeDirection},
+    gsettings::{self, gsettings_subscription},
+    health::{self, HealthScore, Rating, ThermalHistory},
+    history::{self, DrainBaseline, EnergyTotals, History, Sample},
+    icon_theme, instance, night_light,
+    notifications::{self, LowBattery, Notification, Urgency},
+    schedule::{self, interval_subscription, tick_subscription},
+    similar_hardware::typical_battery_life,
+    smart_sleep::{self, InputActivity},
+    state_machine::{AppStateMachine, PowerState},
+    status_notifier::{StatusNotifierEvent, StatusNotifierRequest, status_notifier_subscription},
 };
 use cosmic::{
     Element, Task, app,
     applet::{
-        cosmic_panel_config::PanelAnchor,
+        cosmic_panel_config::{CosmicPanelConfig, PanelAnchor},
         menu_button, padded_control,
         token::subscription::{TokenRequest, TokenUpdate, activation_token_subscription},
     },
     cctk::sctk::reexports::calloop,
+    cosmic_config::{self, CosmicConfigEntry},
     cosmic_theme::Spacing,
     iced::{
         Length, Subscription,
+        event::{self, listen_with},
+        keyboard::{self, key::Named},
         platform_specific::shell::wayland::commands::popup::{destroy_popup, get_popup},
-        widget::{Column, Row, column, container, row},
+        widget::{Column, Row, column, container, progress_bar, row},
         window,
     },
     iced_core::{Alignment, Background, Border, Color, Shadow},
     surface, theme,
-    widget::{divider, horizontal_space, icon, scrollable, slider, text, vertical_space},
+    widget::{
+        button, divider, dropdown, horizontal_space, icon, scrollable, slider, spin_button, text,
+        toggler, tooltip, vertical_space,
+    },
 };
 use cosmic_settings_subscriptions::{
     settings_daemon,
-    upower::{
-        device::{DeviceDbusEvent, device_subscription},
-        kbdbacklight::{
-            KeyboardBacklightRequest, KeyboardBacklightUpdate, kbd_backlight_subscription,
-        },
+    upower::kbdbacklight::{
+        KeyboardBacklightRequest, KeyboardBacklightUpdate, kbd_backlight_subscription,
     },
 };
 use cosmic_time::{Instant, Timeline, anim, chain, id};
@@ -43,27 +102,68 @@ use cosmic_time::{Instant, Timeline, anim, chain, id};
 use std::{collections::HashMap, path::PathBuf, sync::LazyLock, time::Duration};
 use tokio::sync::mpsc::UnboundedSender;
 
-// XXX improve
-// TODO: time to empty varies? needs averaging?
-fn format_duration(duration: Duration) -> String {
-    let secs = duration.as_secs();
-    if secs > 60 {
-        let min = secs / 60;
-        if min > 60 {
-            format!("{}:{:02}", min / 60, min % 60)
-        } else {
-            format!("{}{}", min, fl!("minutes"))
-        }
-    } else {
-        format!("{}{}", secs, fl!("seconds"))
-    }
-}
-
 pub fn run() -> cosmic::iced::Result {
     cosmic::applet::run::<CosmicBatteryApplet>(())
 }
 
 static MAX_CHARGE: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static DOCKED_PROTECTION: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static SMART_SLEEP: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static WAKE_ON_LAN: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static KEEP_AWAKE: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static LINK_DISPLAYS: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static NIGHT_LIGHT: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+static NIGHT_LIGHT_AUTOMATIC: LazyLock<id::Toggler> = LazyLock::new(id::Toggler::unique);
+
+// below this, the popup only shows the essentials
+const LOW_POWER_PERCENT: f64 = 15.0;
+
+const PULSE_PERIOD_MS: u128 = 1500;
+// redraws of the low battery banner, slow enough to stay off the vsync
+const PULSE_REDRAW: Duration = Duration::from_millis(100);
+
+const TRAVEL_CHECK_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::hours(6);
+const TRAVEL_REMIND_LATER: chrono::TimeDelta = chrono::TimeDelta::hours(3);
+
+// coefficient of variation above which the time estimate isn't shown
+const MAX_TIME_VARIATION: f64 = 0.5;
+
+const RESUME_REPORT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const WHAT_IF_MAX_WATTS: f64 = 50.0;
+
+// calibration discharges down to this before charging to full
+const CALIBRATION_EMPTY_PERCENT: f64 = 5.0;
+const CALIBRATION_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::days(182);
+
+// how long the CPU must stay above, or below, the thermal threshold
+const THERMAL_HOT_PERIOD: Duration = Duration::from_secs(30);
+const THERMAL_COOL_PERIOD: Duration = Duration::from_secs(60);
+
+// screen-off drain above this suggests something keeps the system busy
+const HIGH_IDLE_DRAIN_WATTS: f64 = 6.0;
+
+// how long the power draw is sampled after switching profiles
+const PROFILE_SAMPLE_PERIOD: Duration = Duration::from_secs(10);
+
+// swipe distance in logical pixels covering the whole brightness range
+const SWIPE_RANGE: f32 = 300.0;
+// how long the brightness shows next to the icon after scrolling over it
+const SCROLL_FEEDBACK: Duration = Duration::from_secs(1);
+// shown instead of the battery while none is present
+const AC_ICON: &str = "ac-adapter-symbolic";
+// shown beside the panel icon while the screen is kept awake
+const KEEP_AWAKE_ICON: &str = "cosmic-applet-battery-keep-awake-symbolic";
+// charge history taken from UPower at startup, as far back as the chart goes
+const RECORDED_HISTORY: Duration = Duration::from_secs(4 * 60 * 60);
+const RECORDED_HISTORY_POINTS: u32 = 48;
+
+/// Power actions that end every session, so they're confirmed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEnd {
+    Reboot,
+    PowerOff,
+}
 
 #[derive(Clone, Default)]
 struct GPUData {
@@ -75,11 +175,24 @@ struct GPUData {
 #[derive(Clone, Default)]
 struct CosmicBatteryApplet {
     core: cosmic::app::Core,
+    display_server: DisplayServer,
+    /// The panel hides itself until hovered
+    panel_autohide: bool,
+    /// logind seat of this session, from `XDG_SEAT`
+    seat: String,
+    config: BatteryConfig,
+    config_helper: Option<cosmic_config::Config>,
     icon_name: String,
     display_icon_name: String,
     charging_limit: Option<bool>,
     battery_percent: f64,
     on_battery: bool,
+    /// UPower reports no battery, as on desktops or after removing it
+    no_battery: bool,
+    /// Why UPower can't be reached, while it keeps failing
+    upower_error: Option<String>,
+    warning_level: WarningLevel,
+    battery_widget: BatteryWidget,
     gpus: HashMap<PathBuf, GPUData>,
     update_trigger: Option<UnboundedSender<()>>,
     time_remaining: Duration,
@@ -89,14 +202,122 @@ struct CosmicBatteryApplet {
     screen_brightness: Option<i32>,
     popup: Option<window::Id>,
     settings_daemon_sender: Option<UnboundedSender<settings_daemon::Request>>,
+    /// Used for the screen brightness until the settings daemon is reached
+    backlight: Option<Backlight>,
+    /// Monitors with brightness control over DDC/CI
+    external_displays: Vec<ExternalDisplay>,
+    /// Taken the first time the night light comes on
+    gamma: Option<GammaControl>,
+    /// The temperature the night light was last set to
+    night_light_applied: Option<u32>,
     kbd_sender: Option<UnboundedSender<KeyboardBacklightRequest>>,
     power_profile: Power,
     power_profile_sender: Option<UnboundedSender<PowerProfileRequest>>,
     timeline: Timeline,
     token_tx: Option<calloop::channel::Sender<TokenRequest>>,
+    status_notifier_sender: Option<UnboundedSender<StatusNotifierRequest>>,
     zbus_connection: Option<zbus::Connection>,
     dragging_screen_brightness: bool,
     dragging_kbd_brightness: bool,
+    /// Last values written while dragging, so holding a slider still doesn't
+    /// keep writing the same brightness
+    dragged_screen_brightness: Option<i32>,
+    dragged_kbd_brightness: Option<i32>,
+    /// A write of the brightness set by scrolling over the icon is scheduled
+    scroll_write_pending: bool,
+    /// When the icon was last scrolled over, to show the brightness beside it
+    scrolled_at: Option<std::time::Instant>,
+    mem_sleep: Option<MemSleepState>,
+    cpu_governor: Option<CpuGovernor>,
+    idle_config_helper: Option<cosmic_config::Config>,
+    idle_config: IdleConfig,
+    fans: Vec<Fan>,
+    /// Battery powered devices besides the display device, while the popup is open
+    battery_devices: Vec<BatteryDevice>,
+    /// Path of the battery device whose details are shown
+    expanded_battery_device: Option<String>,
+    power_devices: Option<Vec<PowerDevice>>,
+    /// Path of the device whose raw properties are shown
+    selected_power_device: Option<String>,
+    kernel_params: Option<Vec<KernelParam>>,
+    /// Key of the parameter whose help is shown
+    kernel_param_help: Option<String>,
+    usb_devices: Vec<UsbDevice>,
+    power_quality: Option<PowerQuality>,
+    show_power_quality: bool,
+    wake_on_lan: Option<WakeOnLan>,
+    keep_awake: bool,
+    /// Held while keeping the screen awake, dropping it lets the screen sleep
+    inhibitor: Option<Inhibitor>,
+    modem: Option<ModemStatus>,
+    /// Wakeup counters taken as the system went to sleep
+    wakeup_before: Vec<WakeupSource>,
+    /// What woke the system, shown once after resuming
+    resume_report: Option<(std::time::Instant, Vec<ResumeSource>)>,
+    /// Power saver was selected because the CPU ran hot
+    thermal_mitigating: bool,
+    /// Profile to return to once the CPU cools down
+    thermal_restore_profile: Option<Power>,
+    /// Since when the CPU has been above, or below, the thermal threshold
+    thermal_since: Option<(bool, std::time::Instant)>,
+    thunderbolt_devices: Vec<ThunderboltDevice>,
+    /// Profile and brightness to return to once the dock is disconnected
+    dock_restore: Option<(Power, Option<i32>)>,
+    /// Profile and brightness to return to once the low battery power saver ends
+    power_saver_restore: Option<(Power, Option<i32>)>,
+    /// Recent input, from the first idle notification on
+    input_activity: Option<InputActivity>,
+    /// Since when the screen is dimmed before a smart sleep, and the
+    /// brightness to restore
+    smart_sleep: Option<(std::time::Instant, Option<i32>)>,
+    /// Start of the current sleep, when it will move on to hibernation
+    hibernate_pending: Option<(chrono::DateTime<chrono::Local>, HibernateAfter)>,
+    show_usb_devices: bool,
+    history: History,
+    low_battery_notified: Option<LowBattery>,
+    /// Time of the pending scheduled power off
+    shutdown_at: Option<chrono::DateTime<chrono::Local>>,
+    last_shutdown_slot: Option<chrono::NaiveDateTime>,
+    next_travel_check: Option<chrono::DateTime<chrono::Local>>,
+    /// Trip date the charge limit reminder was last sent for
+    trip_reminded: Option<chrono::NaiveDate>,
+    thermal_history: ThermalHistory,
+    /// Typical full-charge runtime of this laptop model
+    typical_battery_life: Option<Duration>,
+    health: Option<HealthScore>,
+    battery_details: Option<BatteryDetails>,
+    /// The chemistry suggestion was sent and awaits an answer
+    chemistry_suggested: bool,
+    show_all_options: bool,
+    power_actions: PowerActions,
+    /// Restart or power off was picked and awaits confirmation
+    confirm_session_end: Option<SessionEnd>,
+    /// Starting cosmic-settings failed, so the popup stays open
+    settings_failed: bool,
+    /// A screen saver is running to lock the screen
+    can_lock: bool,
+    state_machine: AppStateMachine,
+    energy_totals: EnergyTotals,
+    #[cfg(feature = "energy-goals")]
+    energy_goal: EnergyGoal,
+    drain_baseline: DrainBaseline,
+    /// Current and typical discharge rate, while the power draw is unusual
+    unusual_drain: Option<(f64, f64)>,
+    /// Watt-hours currently stored
+    battery_energy: f64,
+    /// Watts flowing in or out of the battery
+    energy_rate: f64,
+    /// Hypothetical power draw for the discharge simulation, while shown
+    what_if_watts: Option<f64>,
+    screen_off_since: Option<chrono::DateTime<chrono::Local>>,
+    /// Mean power while the screen was last off, and while in use before that
+    screen_off_drain: Option<(f64, Option<f64>)>,
+    /// "System default" followed by the icon themes with battery icons
+    icon_theme_options: Vec<String>,
+    /// Labels for [`SCREEN_TIMEOUT_PRESETS`]
+    screen_timeout_options: Vec<String>,
+    /// Battery icon from the configured icon theme, keyed by theme and name
+    themed_icon: Option<(String, Option<PathBuf>)>,
 }
 
 impl CosmicBatteryApplet {
@@ -104,6 +325,10 @@ impl CosmicBatteryApplet {
         percent = percent.clamp(0.0, 100.0);
         self.on_battery = on_battery;
         self.battery_percent = percent;
+        self.battery_widget
+            .update(BatteryWidgetMsg::SetPercent(percent));
+        self.battery_widget
+            .update(BatteryWidgetMsg::SetCharging(!on_battery));
         let battery_percent =
             if self.battery_percent > 95.0 && !self.charging_limit.unwrap_or_default() {
                 100
@@ -132,6 +357,210 @@ impl CosmicBatteryApplet {
         let charging = if on_battery { "" } else { "charging-" };
         self.icon_name =
             format!("cosmic-applet-battery-level-{battery_percent}-{limited}{charging}symbolic",);
+        #[cfg(feature = "energy-goals")]
+        if self
+            .energy_goal
+            .exceeded(f64::from(self.config.daily_energy_budget))
+        {
+            self.icon_name = energy_goal::EXCEEDED_ICON.to_string();
+        }
+        self.update_themed_icon();
+    }
+
+    /// Counts today's energy against the budget, warning once most of it is
+    /// used up.
+    #[cfg(feature = "energy-goals")]
+    fn update_energy_goal(&mut self) -> app::Task<Message> {
+        let budget = f64::from(self.config.daily_energy_budget);
+        let was_exceeded = self.energy_goal.exceeded(budget);
+        let nearing = self.energy_goal.update(
+            chrono::Local::now().date_naive(),
+            self.energy_totals.used,
+            budget,
+        );
+        if self.energy_goal.exceeded(budget) != was_exceeded {
+            self.update_battery(self.battery_percent, self.on_battery);
+        }
+        if !nearing {
+            return Task::none();
+        }
+        self.send_notification(notifications::energy_goal_nearing(
+            self.energy_goal.used,
+            self.config.daily_energy_budget,
+        ))
+    }
+
+    fn update_themed_icon(&mut self) {
+        let Some(theme) = self.config.battery_icon_theme.as_deref() else {
+            self.themed_icon = None;
+            return;
+        };
+        let name = icon_theme::battery_icon_name(self.battery_percent, !self.on_battery);
+        // follows the panel size, so tall panels don't get a scaled-up bitmap
+        let size = self.core.applet.suggested_size(true).0;
+        let key = format!("{theme}/{size}/{name}");
+        if self.themed_icon.as_ref().is_some_and(|(k, _)| *k == key) {
+            return;
+        }
+        self.themed_icon = Some((key, icon_theme::lookup(theme, &name, size)));
+    }
+
+    /// Charge and the time until empty, or until full while charging.
+    fn battery_description(&self) -> String {
+        let variation = self.history.power_variation();
+        match self.state_machine.state() {
+            PowerState::FullyCharged => {
+                return format!("{} ({:.0}%)", fl!("fully-charged"), self.battery_percent);
+            }
+            // UPower doesn't know what the battery is doing
+            PowerState::Idle => return format!("— ({:.0}%)", self.battery_percent),
+            _ => {}
+        }
+        if self.time_remaining.is_zero() {
+            format!("{:.0}%", self.battery_percent)
+        } else if !self.on_battery {
+            // charging estimates are steady enough to show to the minute
+            format!(
+                "{} ({:.0}%)",
+                format::time_remaining(self.time_remaining, true),
+                self.battery_percent
+            )
+        } else if variation.is_some_and(|cv| cv > MAX_TIME_VARIATION) {
+            format!("{} ({:.0}%)", fl!("time-varies"), self.battery_percent)
+        } else {
+            let mut time = duration_to_natural(self.time_remaining);
+            // the estimate scales with the power draw, so its error does too
+            let margin_minutes = variation
+                .map(|cv| (self.time_remaining.as_secs_f64() * cv / 300.0).round() as u64 * 5)
+                .unwrap_or_default();
+            if margin_minutes > 0 {
+                time.push(' ');
+                time.push_str(&fl!("time-margin", minutes = margin_minutes));
+            }
+            format!(
+                "{} {} ({:.0}%)",
+                time,
+                fl!("until-empty"),
+                self.battery_percent
+            )
+        }
+    }
+
+    /// Whether the battery has worn below the configured health.
+    fn low_health(&self) -> bool {
+        self.battery_details
+            .as_ref()
+            .and_then(|details| details.capacity)
+            .is_some_and(|health| health < f64::from(self.config.low_health_percent))
+    }
+
+    /// Header of the battery section, telling where power comes from.
+    fn power_source(&self) -> String {
+        if self.on_battery {
+            fl!("on-battery-power")
+        } else if self.state_machine.state() == PowerState::Charging {
+            fl!("plugged-in-charging")
+        } else {
+            fl!("plugged-in-not-charging")
+        }
+    }
+
+    /// The configured tray mode, falling back to the tray icon where the
+    /// panel button can't be shown.
+    fn tray_mode(&self) -> TrayMode {
+        if self.display_server.supports_panel() {
+            self.config.tray_mode
+        } else {
+            TrayMode::StatusNotifierItem
+        }
+    }
+
+    /// Moves the popup clear of the panel's resting position, so it isn't
+    /// covered while an autohiding panel slides back in.
+    fn autohide_offset(&self) -> (i32, i32) {
+        let (width, height) = self.core.applet.suggested_size(true);
+        let padding = self.core.applet.suggested_padding(true);
+        let thickness = match self.core.applet.anchor {
+            PanelAnchor::Left | PanelAnchor::Right => i32::from(width + 2 * padding),
+            PanelAnchor::Top | PanelAnchor::Bottom => i32::from(height + 2 * padding),
+        };
+        match self.core.applet.anchor {
+            PanelAnchor::Top => (0, thickness),
+            PanelAnchor::Bottom => (0, -thickness),
+            PanelAnchor::Left => (thickness, 0),
+            PanelAnchor::Right => (-thickness, 0),
+        }
+    }
+
+    /// Profile name with its measured power draw relative to balanced.
+    fn profile_name(&self, profile: Power) -> String {
+        let name = match profile {
+            Power::Battery => fl!("battery"),
+            Power::Balanced => return fl!("balanced"),
+            Power::Performance => fl!("performance"),
+        };
+        let milliwatts = |profile: Power| {
+            self.config
+                .profile_power
+                .get(profile.as_str())
+                .map(|power| i64::from(power.milliwatts))
+        };
+        let detail = match milliwatts(profile).zip(milliwatts(Power::Balanced)) {
+            Some((milliwatts, balanced)) => {
+                let watts = format!("{:.0}", (milliwatts - balanced).abs() as f64 / 1000.0);
+                if milliwatts < balanced {
+                    fl!("profile-saves", watts = watts)
+                } else {
+                    fl!("profile-costs", watts = watts)
+                }
+            }
+            None => fl!("profile-calibrating"),
+        };
+        format!("{name} ({detail})")
+    }
+
+    fn update_status_notifier(&self) {
+        if let Some(tx) = &self.status_notifier_sender {
+            let _ = tx.send(if let Some(err) = &self.upower_error {
+                StatusNotifierRequest::Update {
+                    icon_name: self.icon_name.clone(),
+                    tooltip: fl!("upower-unreachable", error = err.as_str()),
+                }
+            } else if self.no_battery {
+                StatusNotifierRequest::Update {
+                    icon_name: AC_ICON.to_string(),
+                    tooltip: fl!("no-battery"),
+                }
+            } else {
+                StatusNotifierRequest::Update {
+                    icon_name: self.icon_name.clone(),
+                    tooltip: self.battery_description(),
+                }
+            });
+        }
+    }
+
+    fn hibernate_message(&self) -> Option<String> {
+        let (started, after) = self.hibernate_pending?;
+        Some(match after {
+            HibernateAfter::Delay(delay) => {
+                let elapsed = (chrono::Local::now() - started)
+                    .to_std()
+                    .unwrap_or_default();
+                fl!(
+                    "hibernate-in",
+                    time = duration_to_natural(delay.saturating_sub(elapsed))
+                )
+            }
+            HibernateAfter::LowBattery => fl!("hibernate-on-low-battery"),
+        })
+    }
+
+    fn battery_icon(&self) -> icon::Handle {
+        match self.themed_icon.as_ref().and_then(|(_, path)| path.clone()) {
+            Some(path) => icon::from_path(path),
+            None => icon::from_name(&*self.icon_name).symbolic(true).handle(),
+        }
     }
 
     fn screen_brightness_percent(&self) -> Option<f64> {
@@ -165,6 +594,527 @@ impl CosmicBatteryApplet {
         self.charging_limit = Some(limit);
         self.update_battery(self.battery_percent, self.on_battery);
     }
+
+    /// Moves the external displays to `percent` of their range, if they're
+    /// linked to the built-in one.
+    fn link_external_brightness(&mut self, percent: f64) {
+        if !self.config.link_displays {
+            return;
+        }
+        for display in &mut self.external_displays {
+            display.brightness = (percent * f64::from(display.max)).round() as u16;
+        }
+    }
+
+    /// Writes the brightness of the external display at `index`, or of all
+    /// of them.
+    fn write_external_brightness(&self, index: Option<usize>) -> app::Task<Message> {
+        Task::batch(
+            self.external_displays
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| index.is_none_or(|index| index == *i))
+                .map(|(_, display)| {
+                    Task::perform(
+                        ddc::set_brightness(display.bus, display.brightness),
+                        |res| match res {
+                            Ok(()) => cosmic::Action::None,
+                            Err(why) => cosmic::Action::App(Message::Errored(why)),
+                        },
+                    )
+                }),
+        )
+    }
+
+    /// Sends the slider value, clamped to the range the backlight reports.
+    fn send_screen_brightness(&self) {
+        let Some(brightness) = self.screen_brightness else {
+            return;
+        };
+        let brightness = brightness.clamp(1, self.max_screen_brightness.unwrap_or(i32::MAX));
+        tracing::debug!("Setting screen brightness to {brightness}");
+        match (&self.settings_daemon_sender, &self.backlight) {
+            (Some(tx), _) => {
+                if tx
+                    .send(settings_daemon::Request::SetDisplayBrightness(brightness))
+                    .is_err()
+                {
+                    tracing::error!("Failed to set display brightness: settings daemon is gone");
+                }
+            }
+            (None, Some(backlight)) => {
+                let name = backlight.name.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = set_brightness(name, brightness).await {
+                        tracing::error!("Failed to set display brightness: {err}");
+                    }
+                });
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn send_kbd_brightness(&self) {
+        let (Some(tx), Some(brightness)) = (&self.kbd_sender, self.kbd_brightness) else {
+            return;
+        };
+        let brightness = brightness.clamp(0, self.max_kbd_brightness.unwrap_or(i32::MAX));
+        tracing::debug!("Setting keyboard brightness to {brightness}");
+        if tx.send(KeyboardBacklightRequest::Set(brightness)).is_err() {
+            tracing::error!("Failed to set keyboard brightness: UPower watch is gone");
+        }
+    }
+
+    fn send_notification(&self, notification: Notification) -> app::Task<Message> {
+        let Some(conn) = self.zbus_connection.clone() else {
+            return Task::none();
+        };
+        Task::perform(notifications::send(conn, notification), |res| match res {
+            Ok(_) => cosmic::Action::None,
+            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+        })
+    }
+
+    /// UPower's warning level, raised by the configured thresholds when
+    /// they're reached first.
+    fn low_battery_level(&self) -> Option<LowBattery> {
+        LowBattery::for_percent(
+            self.battery_percent,
+            self.config.low_battery_percent,
+            self.config.critical_battery_percent,
+        )
+        .max(LowBattery::for_warning_level(self.warning_level))
+    }
+
+    // the banner goes away once charging or back above the warning level
+    fn low_battery_banner(&self) -> Option<LowBattery> {
+        self.on_battery.then(|| self.low_battery_level()).flatten()
+    }
+
+    fn check_low_battery(&mut self) -> app::Task<Message> {
+        if !self.on_battery {
+            self.low_battery_notified = None;
+            return Task::none();
+        }
+        match self.low_battery_level() {
+            Some(level) if self.low_battery_notified < Some(level) => {
+                self.low_battery_notified = Some(level);
+                let mut tasks = Vec::new();
+                if !self.config.silent_notifications {
+                    let time = (!self.time_remaining.is_zero())
+                        .then(|| duration_to_natural(self.time_remaining));
+                    tasks.push(self.send_notification(level.notification(
+                        self.battery_percent,
+                        time,
+                        &self.icon_name,
+                    )));
+                }
+                if level == LowBattery::Critical && self.config.suspend_on_critical {
+                    tasks.push(Task::perform(suspend(), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    }));
+                }
+                Task::batch(tasks)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    // apply docked battery protection for the current dock and AC state
+    fn update_docked_protection(&mut self, docked: bool) -> app::Task<Message> {
+        if !self.config.docked_battery_protection {
+            return Task::none();
+        }
+        if docked
+            && !self.on_battery
+            && self.battery_percent >= f64::from(self.config.charge_limit_threshold)
+        {
+            if self.config.docked_limit_applied || self.charging_limit == Some(true) {
+                return Task::none();
+            }
+            self.config.docked_limit_applied = true;
+            self.write_config();
+            self.set_charging_limit(true);
+            return Task::perform(
+                set_charging_limit(self.config.charge_limit_threshold),
+                |_| cosmic::Action::None,
+            );
+        }
+        if !docked
+            && self.on_battery
+            && self.battery_percent <= 20.0
+            && self.config.docked_limit_applied
+        {
+            self.config.docked_limit_applied = false;
+            self.write_config();
+            self.set_charging_limit(false);
+            return Task::perform(clear_charging_limit(), |res| match res {
+                Ok(()) => cosmic::Action::None,
+                Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+            });
+        }
+        Task::none()
+    }
+
+    // low on battery, only the essentials are shown until expanded
+    fn is_condensed(&self) -> bool {
+        self.config.low_power_density_ui
+            && self.on_battery
+            && self.battery_percent < LOW_POWER_PERCENT
+            && !self.show_all_options
+    }
+
+    /// Closes the popup first, so it isn't left on screen across a suspend.
+    fn close_popup_then(&mut self, task: app::Task<Message>) -> app::Task<Message> {
+        match self.popup.take() {
+            Some(popup) => Task::batch([destroy_popup(popup), task]),
+            None => task,
+        }
+    }
+
+    fn run_swipe_action(&mut self, action: SwipeAction) -> app::Task<Message> {
+        let osd = match action {
+            SwipeAction::None => return Task::none(),
+            SwipeAction::TogglePowerSaver => {
+                let profile = if self.power_profile == Power::Battery {
+                    Power::Balanced
+                } else {
+                    Power::Battery
+                };
+                if let Some(tx) = self.power_profile_sender.as_ref() {
+                    let _ = tx.send(PowerProfileRequest::Set(profile));
+                }
+                match profile {
+                    Power::Battery => fl!("battery"),
+                    Power::Balanced => fl!("balanced"),
+                    Power::Performance => fl!("performance"),
+                }
+            }
+            SwipeAction::CycleBrightness => {
+                let (Some(max), Some(brightness)) =
+                    (self.max_screen_brightness, self.screen_brightness)
+                else {
+                    return Task::none();
+                };
+                // 25% → 50% → 75% → 100% → 25%
+                let quarter = (brightness * 4 / max.max(1)) % 4 + 1;
+                let brightness = (max * quarter / 4).max(1);
+                self.screen_brightness = Some(brightness);
+                self.update_display();
+                self.send_screen_brightness();
+                fl!("swipe-brightness", percent = quarter * 25)
+            }
+            SwipeAction::Suspend => return self.update(Message::Suspend),
+        };
+        self.send_notification(Notification {
+            summary: osd,
+            body: String::new(),
+            icon: self.icon_name.clone(),
+            urgency: Urgency::Low,
+            resident: false,
+            expire_timeout: 1500,
+            actions: Vec::new(),
+        })
+    }
+
+    /// Switches to power saver while the CPU stays above the configured
+    /// temperature, and back once it has stayed below it.
+    fn update_thermal_mitigation(&mut self, temperature: f64) -> app::Task<Message> {
+        let hot = temperature > f64::from(self.config.thermal_mitigation_threshold);
+        let now = std::time::Instant::now();
+        let since = match self.thermal_since {
+            Some((was_hot, since)) if was_hot == hot => since,
+            _ => {
+                self.thermal_since = Some((hot, now));
+                now
+            }
+        };
+        let elapsed = now.duration_since(since);
+
+        if hot
+            && !self.thermal_mitigating
+            && self.power_profile != Power::Battery
+            && elapsed >= THERMAL_HOT_PERIOD
+        {
+            let Some(tx) = self.power_profile_sender.as_ref() else {
+                return Task::none();
+            };
+            let _ = tx.send(PowerProfileRequest::Set(Power::Battery));
+            self.thermal_mitigating = true;
+            self.thermal_restore_profile = Some(self.power_profile);
+            return self.send_notification(notifications::thermal_mitigation());
+        }
+        if !hot && self.thermal_mitigating && elapsed >= THERMAL_COOL_PERIOD {
+            self.thermal_mitigating = false;
+            if let (Some(profile), Some(tx)) = (
+                self.thermal_restore_profile.take(),
+                self.power_profile_sender.as_ref(),
+            ) {
+                let _ = tx.send(PowerProfileRequest::Set(profile));
+            }
+        }
+        Task::none()
+    }
+
+    /// Full-charge runtime at the average discharge rate seen so far, next to
+    /// the typical runtime of this model.
+    fn battery_life_comparison(&self) -> Option<(Duration, Duration)> {
+        let typical = self.typical_battery_life?;
+        let energy_full = self.battery_details.as_ref()?.energy_full?;
+        let watts = self.history.mean_watts(|_| true).filter(|w| *w > 0.0)?;
+        Some((
+            Duration::from_secs_f64(energy_full / watts * 3600.0),
+            typical,
+        ))
+    }
+
+    /// The current power draw, signed while charging. `None` without a
+    /// rate or once fully charged.
+    fn energy_rate_label(&self) -> Option<String> {
+        if self.energy_rate <= 0.0 {
+            return None;
+        }
+        match self.state_machine.state() {
+            PowerState::Charging => Some(format!("+{:.1} W", self.energy_rate)),
+            PowerState::Discharging | PowerState::Critical | PowerState::Shutdown => {
+                Some(format!("{:.1} W", self.energy_rate))
+            }
+            PowerState::Idle | PowerState::FullyCharged => None,
+        }
+    }
+
+    /// Switches to the dock profile and brightness while the remembered dock is
+    /// connected, and back once it's gone.
+    fn update_dock_profile(&mut self) {
+        // the profile to return to isn't known yet
+        if self.power_profile_sender.is_none() {
+            return;
+        }
+        let docked = self.config.dock_uuid.as_ref().is_some_and(|uuid| {
+            self.thunderbolt_devices
+                .iter()
+                .any(|device| &device.uuid == uuid)
+        });
+        let (profile, brightness) = match (docked, self.dock_restore) {
+            (true, None) => {
+                self.dock_restore = Some((self.power_profile, self.screen_brightness));
+                let profile = self
+                    .config
+                    .dock_profile
+                    .as_deref()
+                    .and_then(Power::from_name)
+                    .unwrap_or(Power::Performance);
+                let percent = self.config.dock_brightness.unwrap_or(100).min(100) as i32;
+                let brightness = self.max_screen_brightness.map(|max| max * percent / 100);
+                (profile, brightness)
+            }
+            (false, Some(restore)) => {
+                self.dock_restore = None;
+                restore
+            }
+            _ => return,
+        };
+        if let Some(tx) = self.power_profile_sender.as_ref() {
+            let _ = tx.send(PowerProfileRequest::Set(profile));
+        }
+        if brightness.is_some() {
+            self.screen_brightness = brightness;
+            self.update_display();
+            self.send_screen_brightness();
+        }
+    }
+
+    /// Switches to power saver, and dims the screen, once the charge drops
+    /// below the configured level on battery power. Only AC power ends it, so
+    /// the charge hovering around the level doesn't switch back and forth.
+    fn update_power_saver(&mut self) {
+        // the profile to return to isn't known yet
+        if self.power_profile_sender.is_none() {
+            return;
+        }
+        let threshold = self.config.power_saver_percent;
+        let low = self.on_battery && threshold > 0 && self.battery_percent < f64::from(threshold);
+        let (profile, brightness) = match (low, self.power_saver_restore) {
+            (true, None) => {
+                let brightness = self
+                    .config
+                    .power_saver_brightness
+                    .zip(self.max_screen_brightness)
+                    .map(|(percent, max)| max * percent.min(100) as i32 / 100)
+                    .filter(|dimmed| {
+                        self.screen_brightness
+                            .is_none_or(|current| *dimmed < current)
+                    });
+                // the brightness is only put back if it was dimmed
+                self.power_saver_restore =
+                    Some((self.power_profile, brightness.and(self.screen_brightness)));
+                (Power::Battery, brightness)
+            }
+            (false, Some(restore)) if !self.on_battery => {
+                self.power_saver_restore = None;
+                restore
+            }
+            _ => return,
+        };
+        if let Some(tx) = self.power_profile_sender.as_ref() {
+            let _ = tx.send(PowerProfileRequest::Set(profile));
+        }
+        if brightness.is_some() {
+            self.screen_brightness = brightness;
+            self.update_display();
+            self.send_screen_brightness();
+        }
+    }
+
+    /// Dims the screen once a long break seems likely, to suspend unless
+    /// there's input within the grace period.
+    fn check_smart_sleep(&mut self) -> app::Task<Message> {
+        let likely = self
+            .input_activity
+            .as_ref()
+            .and_then(InputActivity::break_confidence)
+            .is_some_and(|confidence| confidence >= smart_sleep::MIN_CONFIDENCE);
+        if !likely || !self.config.smart_sleep || !self.on_battery || self.smart_sleep.is_some() {
+            return Task::none();
+        }
+
+        let previous = self.screen_brightness;
+        if let (Some(max), Some(brightness)) = (self.max_screen_brightness, previous) {
+            self.screen_brightness = Some(brightness.min(max * smart_sleep::DIM_PERCENT / 100));
+            self.update_display();
+            self.send_screen_brightness();
+        }
+        self.smart_sleep = Some((std::time::Instant::now(), previous));
+        Task::perform(tokio::time::sleep(smart_sleep::GRACE_PERIOD), |()| {
+            cosmic::Action::App(Message::SmartSleepTimeout)
+        })
+    }
+
+    fn cancel_smart_sleep(&mut self) {
+        let Some((_, previous)) = self.smart_sleep.take() else {
+            return;
+        };
+        // start over, or the screen would dim again at the next pause
+        self.input_activity = None;
+        if previous.is_some() {
+            self.screen_brightness = previous;
+            self.update_display();
+            self.send_screen_brightness();
+        }
+    }
+
+    /// Only a calibration run from the applet is on record, so the battery
+    /// is never assumed to be due without one.
+    fn calibration_due(&self) -> bool {
+        let today = chrono::Local::now().date_naive();
+        self.config.calibration.is_none()
+            && self
+                .config
+                .last_calibration
+                .is_some_and(|last| today - last > CALIBRATION_INTERVAL)
+            && self
+                .config
+                .calibration_dismissed
+                .is_none_or(|dismissed| today - dismissed > CALIBRATION_INTERVAL)
+    }
+
+    /// Moves on to charging once empty, and finishes once full.
+    fn advance_calibration(&mut self, percent: f64, state: BatteryState) -> app::Task<Message> {
+        match self.config.calibration {
+            Some(CalibrationStep::Discharge { restore_limit })
+                if percent <= CALIBRATION_EMPTY_PERCENT =>
+            {
+                self.config.calibration = Some(CalibrationStep::Charge { restore_limit });
+                self.write_config();
+            }
+            Some(step @ CalibrationStep::Charge { .. })
+                if state == BatteryState::FullyCharged || percent >= 100.0 =>
+            {
+                self.config.calibration = None;
+                self.config.last_calibration = Some(chrono::Local::now().date_naive());
+                self.write_config();
+                return self.restore_calibration_limit(step);
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    fn restore_calibration_limit(&mut self, step: CalibrationStep) -> app::Task<Message> {
+        let (CalibrationStep::Discharge { restore_limit }
+        | CalibrationStep::Charge { restore_limit }) = step;
+        if !restore_limit {
+            return Task::none();
+        }
+        self.set_charging_limit(true);
+        Task::perform(
+            set_charging_limit(self.config.charge_limit_threshold),
+            |res| match res {
+                Ok(()) => cosmic::Action::None,
+                Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+            },
+        )
+    }
+
+    fn export_gsettings(&self) -> app::Task<Message> {
+        Task::perform(gsettings::export(self.config.clone()), |res| match res {
+            Ok(()) => cosmic::Action::None,
+            Err(why) => cosmic::Action::App(Message::Errored(why)),
+        })
+    }
+
+    /// Saves the brightness last reported by the hardware, so the sliders
+    /// start out there on the next launch.
+    fn remember_brightness(&mut self) {
+        let Some(helper) = &self.config_helper else {
+            return;
+        };
+        let screen = self.screen_brightness.zip(self.max_screen_brightness);
+        if screen.is_some() && screen != self.config.screen_brightness_fallback {
+            if let Err(err) = self.config.set_screen_brightness_fallback(helper, screen) {
+                tracing::error!(?err, "Error writing config");
+            }
+        }
+        let kbd = self.kbd_brightness.zip(self.max_kbd_brightness);
+        if kbd.is_some() && kbd != self.config.kbd_brightness_fallback {
+            if let Err(err) = self.config.set_kbd_brightness_fallback(helper, kbd) {
+                tracing::error!(?err, "Error writing config");
+            }
+        }
+    }
+
+    /// The temperature the night light should have now, if it's on.
+    fn night_light_temperature(&self) -> Option<u32> {
+        let on = self.config.night_light
+            || (self.config.night_light_automatic
+                && night_light::is_night(chrono::Local::now(), self.config.night_light_location));
+        on.then_some(self.config.night_light_temperature)
+    }
+
+    /// Tints the screen as the night light should be now, connecting to the
+    /// compositor the first time it comes on.
+    fn update_night_light(&mut self) {
+        let temperature = self.night_light_temperature();
+        if temperature == self.night_light_applied {
+            return;
+        }
+        self.night_light_applied = temperature;
+        if temperature.is_some() && self.gamma.is_none() {
+            self.gamma = Some(GammaControl::spawn());
+        }
+        if let Some(gamma) = &self.gamma {
+            gamma.set(temperature);
+        }
+    }
+
+    fn write_config(&self) {
+        if let Some(helper) = &self.config_helper {
+            if let Err(err) = self.config.write_entry(helper) {
+                tracing::error!(?err, "Error writing config");
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -174,11 +1124,110 @@ enum Message {
     SetKbdBrightness(i32),
     ReleaseKbdBrightness,
     SetScreenBrightness(i32),
+    /// A key the popup's widgets left alone
+    PopupKey(Named),
     SetKbdBrightnessDebounced,
     SetScreenBrightnessDebounced,
+    SwipeScreenBrightness(f32),
+    ScrollScreenBrightness(i32),
+    WriteScrolledBrightness,
+    ScrollFeedbackTimeout,
+    Swipe(SwipeDirection),
+    ShowAllOptions,
+    Suspend,
+    Hibernate,
+    ConfirmSessionEnd(Option<SessionEnd>),
+    EndSession(SessionEnd),
+    PowerActions(PowerActions),
+    CanLock(bool),
+    Lock,
     ReleaseScreenBrightness,
+    ExternalDisplays(Vec<ExternalDisplay>),
+    SetExternalBrightness(usize, u16),
+    ReleaseExternalBrightness(usize),
+    SetLinkDisplays(chain::Toggler, bool),
+    SetNightLight(chain::Toggler, bool),
+    SetNightLightTemperature(u32),
+    ReleaseNightLightTemperature,
+    SetNightLightAutomatic(chain::Toggler, bool),
+    NightLightTick,
     InitChargingLimit(Option<bool>),
+    InitMemSleep(Option<MemSleepState>),
+    InitCpuGovernor(Option<CpuGovernor>),
+    SetCpuGovernor(usize),
+    SetScreenTimeout(usize),
+    SetAcSuspendDelay(u32),
+    SetBatterySuspendDelay(u32),
+    IdleConfigChanged(IdleConfig),
+    SetMemSleep(MemSleep),
+    StatusNotifier(StatusNotifierEvent),
+    PanelAutohide(bool),
+    ScreenSaver(bool),
+    ToggleWhatIf,
+    SetWhatIfWatts(f64),
+    Sleep(bool),
+    WakeupSnapshot(Vec<WakeupSource>),
+    HibernatePending(Option<HibernateAfter>),
+    ResumeReport(Vec<ResumeSource>),
+    ResumeReportTimeout,
+    DismissResumeReport,
+    BatteryDevices(Vec<BatteryDevice>),
+    RecordedHistory(Vec<RecordedCharge>),
+    ToggleBatteryDevice(String),
+    TogglePowerDevices,
+    PowerDevices(Vec<PowerDevice>),
+    SelectPowerDevice(String),
+    ToggleKernelParams,
+    KernelParams(Vec<KernelParam>),
+    KernelParamHelp(String),
+    ToggleUsbDevices,
+    TogglePowerQuality,
+    PowerQuality(Option<PowerQuality>),
+    UsbDevices(Vec<UsbDevice>),
+    SetUsbAutosuspend(String, bool),
+    StartCalibration,
+    CancelCalibration,
+    DismissCalibration,
+    SetSmartSleep(chain::Toggler, bool),
+    SetKeepAwake(chain::Toggler, bool),
+    KeepAwake(Option<Inhibitor>),
+    InputIdle(bool),
+    SmartSleepTick,
+    SmartSleepTimeout,
+    CancelSmartSleep,
+    EnableStorageMode,
+    StorageModeEnabled((u8, u8)),
+    DisableStorageMode,
+    ConfigChanged(BatteryConfig),
+    Fans(Vec<Fan>),
+    TypicalBatteryLife(Option<Duration>),
+    GSettingsChanged(String, String),
+    CpuTemperature(f64),
+    ExportHistory,
+    HistoryExported(Option<PathBuf>),
     SetChargingLimit(chain::Toggler, bool),
+    SetChargeLimitThreshold(u8),
+    SetDockedProtection(chain::Toggler, bool),
+    WakeOnLan(Option<WakeOnLan>),
+    SetWakeOnLan(chain::Toggler, bool),
+    Modem(Option<ModemStatus>),
+    SetModemEnabled(bool),
+    Docked(bool),
+    ThunderboltDevices(Vec<ThunderboltDevice>),
+    RememberDock(String),
+    ForgetDock,
+    AcStateChanged(bool),
+    IconThemes(Vec<String>),
+    SetIconTheme(usize),
+    ScheduleTick,
+    #[cfg(feature = "energy-goals")]
+    EnergyGoalTick,
+    TravelTick,
+    TripTomorrow(Option<chrono::NaiveDate>),
+    TripAction(Option<String>),
+    ChemistryAction(Chemistry, Option<String>),
+    HealthSample(Option<BatteryDetails>, Option<Duration>),
+    CancelShutdown,
     KeyboardBacklight(KeyboardBacklightUpdate),
     UpowerDevice(DeviceDbusEvent),
     GpuInit(UnboundedSender<()>),
@@ -189,10 +1238,15 @@ enum Message {
     InitProfile(UnboundedSender<PowerProfileRequest>, Power),
     Profile(Power),
     SelectProfile(Power),
+    ProfilePowerSample(Power, Option<f64>),
     Frame(Instant),
+    Pulse,
     Token(TokenUpdate),
     OpenSettings,
+    /// Whether cosmic-settings could be started
+    SettingsSpawned(bool),
     SettingsDaemon(settings_daemon::Event),
+    Backlight(Option<Backlight>),
     ZbusConnection(zbus::Result<zbus::Connection>),
     Surface(surface::Action),
 }
@@ -204,22 +1258,103 @@ impl cosmic::Application for CosmicBatteryApplet {
     const APP_ID: &'static str = config::APP_ID;
 
     fn init(core: cosmic::app::Core, _flags: Self::Flags) -> (Self, app::Task<Self::Message>) {
+        let display_server = DisplayServer::detect();
+        tracing::info!("Display server: {}", display_server.as_str());
+        if !display_server.supports_panel() {
+            tracing::info!("Panel integration needs Wayland, using the tray icon instead");
+        }
         let zbus_session_cmd = Task::perform(zbus::Connection::session(), |res| {
             cosmic::Action::App(Message::ZbusConnection(res))
         });
         let init_charging_limit_cmd = Task::perform(get_charging_limit(), |limit| {
             cosmic::Action::App(Message::InitChargingLimit(limit.ok()))
         });
+        let init_mem_sleep_cmd = Task::perform(get_mem_sleep(), |state| {
+            cosmic::Action::App(Message::InitMemSleep(state))
+        });
+        let init_governor_cmd = Task::perform(get_governor(), |governor| {
+            cosmic::Action::App(Message::InitCpuGovernor(governor))
+        });
+        let typical_battery_life_cmd = Task::perform(typical_battery_life(), |life| {
+            cosmic::Action::App(Message::TypicalBatteryLife(life))
+        });
+        let backlight_cmd = Task::perform(backlight(), |backlight| {
+            cosmic::Action::App(Message::Backlight(backlight))
+        });
+        let can_lock_cmd = Task::perform(can_lock(), |res| {
+            cosmic::Action::App(Message::CanLock(res.unwrap_or_default()))
+        });
+        let power_actions_cmd = Task::perform(power_actions(), |res| match res {
+            Ok(actions) => cosmic::Action::App(Message::PowerActions(actions)),
+            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+        });
+        let config_helper = cosmic_config::Config::new(Self::APP_ID, BatteryConfig::VERSION).ok();
+        let (idle_config_helper, idle_config) = idle_config().unzip();
+        let config = config_helper
+            .as_ref()
+            .map(|helper| {
+                BatteryConfig::get_entry(helper).unwrap_or_else(|(errors, config)| {
+                    for err in errors {
+                        tracing::error!(?err, "Error loading config");
+                    }
+                    config
+                })
+            })
+            .unwrap_or_default();
+        let (screen_brightness, max_screen_brightness) = config.screen_brightness_fallback.unzip();
+        // a remembered keyboard brightness mustn't show a slider for a keyboard
+        // that's no longer there
+        let (kbd_brightness, max_kbd_brightness) = config
+            .kbd_brightness_fallback
+            .filter(|_| has_kbd_backlight())
+            .unzip();
+        // the COSMIC config wins over whatever GSettings held before
+        let gsettings_cmd = if config.gsettings_sync {
+            Task::perform(gsettings::export(config.clone()), |res| match res {
+                Ok(()) => cosmic::Action::None,
+                Err(why) => cosmic::Action::App(Message::Errored(why)),
+            })
+        } else {
+            Task::none()
+        };
         (
             Self {
                 core,
+                display_server,
+                seat: std::env::var("XDG_SEAT").unwrap_or_else(|_| PRIMARY_SEAT.to_string()),
+                screen_brightness,
+                max_screen_brightness,
+                kbd_brightness,
+                max_kbd_brightness,
+                config,
+                config_helper,
+                idle_config_helper,
+                idle_config: idle_config.unwrap_or_default(),
                 icon_name: "battery-symbolic".to_string(),
                 display_icon_name: "display-brightness-symbolic".to_string(),
                 token_tx: None,
+                screen_timeout_options: SCREEN_TIMEOUT_PRESETS
+                    .iter()
+                    .map(|&minutes| match minutes {
+                        0 => fl!("never"),
+                        minutes => fl!("minutes-value", minutes = minutes),
+                    })
+                    .collect(),
 
                 ..Default::default()
             },
-            Task::batch(vec![zbus_session_cmd, init_charging_limit_cmd]),
+            Task::batch(vec![
+                zbus_session_cmd,
+                init_charging_limit_cmd,
+                init_mem_sleep_cmd,
+                init_governor_cmd,
+                typical_battery_life_cmd,
+                power_actions_cmd,
+                can_lock_cmd,
+                backlight_cmd,
+                gsettings_cmd,
+                cosmic::task::message(Message::NightLightTick),
+            ]),
         )
     }
 
@@ -234,45 +1369,181 @@ impl cosmic::Application for CosmicBatteryApplet {
     fn update(&mut self, message: Self::Message) -> app::Task<Self::Message> {
         match message {
             Message::Frame(now) => self.timeline.now(now),
+            // only redraws the low battery banner
+            Message::Pulse => {}
             Message::SetKbdBrightness(brightness) => {
                 self.kbd_brightness = Some(brightness);
 
                 if !self.dragging_kbd_brightness {
                     self.dragging_kbd_brightness = true;
+                    self.dragged_kbd_brightness = None;
                     return cosmic::task::message(Message::SetKbdBrightnessDebounced);
                 }
             }
             Message::SetScreenBrightness(brightness) => {
                 self.screen_brightness = Some(brightness);
+                if let Some(percent) = self.screen_brightness_percent() {
+                    self.link_external_brightness(percent);
+                }
                 if !self.dragging_screen_brightness {
                     self.dragging_screen_brightness = true;
+                    self.dragged_screen_brightness = None;
                     self.update_display();
                     return cosmic::task::message(Message::SetScreenBrightnessDebounced);
                 }
             }
-            Message::SetKbdBrightnessDebounced => {
-                if !self.dragging_kbd_brightness {
-                    return Task::none();
+            Message::PopupKey(key) => match key {
+                Named::Escape => return self.close_popup_then(Task::none()),
+                Named::ArrowDown => return cosmic::iced::widget::focus_next(),
+                Named::ArrowUp => return cosmic::iced::widget::focus_previous(),
+                Named::PageUp | Named::PageDown => {
+                    let (Some(max), Some(brightness)) =
+                        (self.max_screen_brightness, self.screen_brightness)
+                    else {
+                        return Task::none();
+                    };
+                    let step = (max / 10).max(1);
+                    let step = if key == Named::PageUp { step } else { -step };
+                    self.screen_brightness = Some((brightness + step).clamp(1, max));
+                    self.update_display();
+                    self.send_screen_brightness();
                 }
-                if let Some(tx) = &self.kbd_sender {
-                    if let Some(b) = self.kbd_brightness {
-                        let _ = tx.send(KeyboardBacklightRequest::Set(b));
-                    }
+                _ => {}
+            },
+            Message::SwipeScreenBrightness(dy) => {
+                let (Some(max), Some(brightness)) =
+                    (self.max_screen_brightness, self.screen_brightness)
+                else {
+                    return Task::none();
+                };
+                // swiping up brightens
+                let step = (-dy / SWIPE_RANGE * max as f32).round() as i32;
+                let brightness = (brightness + step).clamp(1, max);
+                if step == 0 || Some(brightness) == self.screen_brightness {
+                    return Task::none();
                 }
-                return cosmic::iced::Task::perform(
-                    tokio::time::sleep(Duration::from_millis(200)),
-                    |_| cosmic::Action::App(Message::SetKbdBrightnessDebounced),
-                );
+                self.screen_brightness = Some(brightness);
+                self.update_display();
+                self.send_screen_brightness();
             }
-            Message::SetScreenBrightnessDebounced => {
-                if !self.dragging_screen_brightness {
+            Message::ScrollScreenBrightness(steps) => {
+                let (Some(max), Some(brightness)) =
+                    (self.max_screen_brightness, self.screen_brightness)
+                else {
                     return Task::none();
+                };
+                let step = (max * self.config.scroll_brightness_step as i32 / 100).max(1);
+                self.screen_brightness = Some((brightness + steps * step).clamp(1, max));
+                self.update_display();
+                self.scrolled_at = Some(std::time::Instant::now());
+                let mut tasks = vec![Task::perform(tokio::time::sleep(SCROLL_FEEDBACK), |_| {
+                    cosmic::Action::App(Message::ScrollFeedbackTimeout)
+                })];
+                // writes are spaced out as they are for the sliders
+                if !self.scroll_write_pending {
+                    self.scroll_write_pending = true;
+                    tasks.push(Task::perform(
+                        tokio::time::sleep(Duration::from_millis(200)),
+                        |_| cosmic::Action::App(Message::WriteScrolledBrightness),
+                    ));
                 }
-
-                if let Some(tx) = &self.settings_daemon_sender {
-                    if let Some(b) = self.screen_brightness {
-                        let _ = tx.send(settings_daemon::Request::SetDisplayBrightness(b));
-                    }
+                return Task::batch(tasks);
+            }
+            Message::WriteScrolledBrightness => {
+                self.scroll_write_pending = false;
+                self.send_screen_brightness();
+            }
+            Message::ScrollFeedbackTimeout => {
+                if self
+                    .scrolled_at
+                    .is_some_and(|at| at.elapsed() >= SCROLL_FEEDBACK)
+                {
+                    self.scrolled_at = None;
+                }
+            }
+            Message::ShowAllOptions => {
+                self.show_all_options = true;
+            }
+            Message::Suspend => {
+                return self.close_popup_then(Task::perform(suspend(), |res| match res {
+                    Ok(()) => cosmic::Action::None,
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                }));
+            }
+            Message::Hibernate => {
+                return self.close_popup_then(Task::perform(hibernate(), |res| match res {
+                    Ok(()) => cosmic::Action::None,
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                }));
+            }
+            Message::ConfirmSessionEnd(action) => {
+                self.confirm_session_end = action;
+            }
+            Message::EndSession(action) => {
+                self.confirm_session_end = None;
+                let task = match action {
+                    SessionEnd::Reboot => Task::perform(reboot(), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    }),
+                    SessionEnd::PowerOff => Task::perform(power_off(), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    }),
+                };
+                return self.close_popup_then(task);
+            }
+            Message::PowerActions(actions) => {
+                self.power_actions = actions;
+            }
+            Message::CanLock(can_lock) => {
+                self.can_lock = can_lock;
+            }
+            Message::Lock => {
+                return self.close_popup_then(Task::perform(lock(), |res| match res {
+                    Ok(()) => cosmic::Action::None,
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                }));
+            }
+            Message::Swipe(direction) => {
+                let action = match direction {
+                    SwipeDirection::Left => self.config.swipe_left,
+                    SwipeDirection::Right => self.config.swipe_right,
+                    SwipeDirection::Down => self.config.swipe_down,
+                    SwipeDirection::Up => SwipeAction::None,
+                };
+                if action == SwipeAction::None {
+                    return Task::none();
+                }
+                // the touch that started the swipe also toggled the popup
+                let mut tasks = Vec::new();
+                if let Some(popup) = self.popup.take() {
+                    tasks.push(destroy_popup(popup));
+                }
+                tasks.push(self.run_swipe_action(action));
+                return Task::batch(tasks);
+            }
+            Message::SetKbdBrightnessDebounced => {
+                if !self.dragging_kbd_brightness {
+                    return Task::none();
+                }
+                if self.dragged_kbd_brightness != self.kbd_brightness {
+                    self.dragged_kbd_brightness = self.kbd_brightness;
+                    self.send_kbd_brightness();
+                }
+                return cosmic::iced::Task::perform(
+                    tokio::time::sleep(Duration::from_millis(200)),
+                    |_| cosmic::Action::App(Message::SetKbdBrightnessDebounced),
+                );
+            }
+            Message::SetScreenBrightnessDebounced => {
+                if !self.dragging_screen_brightness {
+                    return Task::none();
+                }
+
+                if self.dragged_screen_brightness != self.screen_brightness {
+                    self.dragged_screen_brightness = self.screen_brightness;
+                    self.send_screen_brightness();
                 }
                 return cosmic::iced::Task::perform(
                     tokio::time::sleep(Duration::from_millis(200)),
@@ -281,22 +1552,69 @@ impl cosmic::Application for CosmicBatteryApplet {
             }
             Message::ReleaseKbdBrightness => {
                 self.dragging_kbd_brightness = false;
-                if let Some(tx) = &self.kbd_sender {
-                    if let Some(b) = self.kbd_brightness {
-                        let _ = tx.send(KeyboardBacklightRequest::Set(b));
-                    }
+                if self.dragged_kbd_brightness != self.kbd_brightness {
+                    self.send_kbd_brightness();
                 }
             }
             Message::ReleaseScreenBrightness => {
                 self.dragging_screen_brightness = false;
 
                 self.update_display();
-                if let Some(tx) = &self.settings_daemon_sender {
-                    if let Some(b) = self.screen_brightness {
-                        let _ = tx.send(settings_daemon::Request::SetDisplayBrightness(b));
+                if self.dragged_screen_brightness != self.screen_brightness {
+                    self.send_screen_brightness();
+                }
+                if self.config.link_displays {
+                    return self.write_external_brightness(None);
+                }
+            }
+            Message::ExternalDisplays(displays) => {
+                self.external_displays = displays;
+            }
+            Message::SetExternalBrightness(index, brightness) => {
+                let Some(display) = self.external_displays.get_mut(index) else {
+                    return Task::none();
+                };
+                display.brightness = brightness;
+                let percent = f64::from(brightness) / f64::from(display.max);
+                if self.config.link_displays {
+                    self.link_external_brightness(percent);
+                    if let Some(max) = self.max_screen_brightness {
+                        self.screen_brightness =
+                            Some(((percent * max as f64).round() as i32).max(1));
+                        self.update_display();
                     }
                 }
             }
+            Message::ReleaseExternalBrightness(index) => {
+                if !self.config.link_displays {
+                    return self.write_external_brightness(Some(index));
+                }
+                self.send_screen_brightness();
+                return self.write_external_brightness(None);
+            }
+            Message::SetLinkDisplays(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.config.link_displays = enable;
+                self.write_config();
+            }
+            Message::SetNightLight(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.config.night_light = enable;
+                self.write_config();
+                self.update_night_light();
+            }
+            Message::SetNightLightTemperature(kelvin) => {
+                self.config.night_light_temperature = kelvin;
+                self.update_night_light();
+            }
+            Message::ReleaseNightLightTemperature => self.write_config(),
+            Message::SetNightLightAutomatic(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.config.night_light_automatic = enable;
+                self.write_config();
+                self.update_night_light();
+            }
+            Message::NightLightTick => self.update_night_light(),
             Message::InitChargingLimit(enable) => {
                 if let Some(enable) = enable {
                     self.set_charging_limit(enable);
@@ -307,11 +1625,636 @@ impl cosmic::Application for CosmicBatteryApplet {
                 self.set_charging_limit(enable);
 
                 if enable {
-                    return cosmic::iced::Task::perform(set_charging_limit(), |_| {
-                        cosmic::Action::None
+                    return cosmic::iced::Task::perform(
+                        set_charging_limit(self.config.charge_limit_threshold),
+                        |_| cosmic::Action::None,
+                    );
+                }
+                return Task::perform(clear_charging_limit(), |res| match res {
+                    Ok(()) => cosmic::Action::None,
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                });
+            }
+            Message::SetChargeLimitThreshold(threshold) => {
+                self.config.charge_limit_threshold = threshold;
+                self.write_config();
+                if self.charging_limit == Some(true) {
+                    return Task::perform(set_charging_limit(threshold), |_| cosmic::Action::None);
+                }
+            }
+            Message::InitMemSleep(state) => {
+                self.mem_sleep = state;
+            }
+            Message::IdleConfigChanged(config) => {
+                self.idle_config = config;
+            }
+            Message::SetScreenTimeout(index) => {
+                let Some(minutes) = SCREEN_TIMEOUT_PRESETS.get(index) else {
+                    return Task::none();
+                };
+                if let Some(helper) = &self.idle_config_helper {
+                    self.idle_config.set_screen_off_minutes(helper, *minutes);
+                }
+            }
+            Message::SetAcSuspendDelay(minutes) => {
+                let delays = SuspendDelays {
+                    ac: minutes,
+                    ..self.idle_config.suspend_delays()
+                };
+                if let Some(helper) = &self.idle_config_helper {
+                    self.idle_config.set_suspend_delays(helper, delays);
+                }
+            }
+            Message::SetBatterySuspendDelay(minutes) => {
+                let delays = SuspendDelays {
+                    battery: minutes,
+                    ..self.idle_config.suspend_delays()
+                };
+                if let Some(helper) = &self.idle_config_helper {
+                    self.idle_config.set_suspend_delays(helper, delays);
+                }
+            }
+            Message::InitCpuGovernor(governor) => {
+                self.cpu_governor = governor;
+            }
+            Message::SetCpuGovernor(index) => {
+                let Some(governor) = self
+                    .cpu_governor
+                    .as_ref()
+                    .and_then(|g| g.available.get(index).cloned())
+                else {
+                    return Task::none();
+                };
+                return Task::perform(set_governor(governor), |res| match res {
+                    Ok(governor) => cosmic::Action::App(Message::InitCpuGovernor(Some(governor))),
+                    Err(why) => cosmic::Action::App(Message::Errored(why)),
+                });
+            }
+            Message::SetMemSleep(state) => {
+                return Task::perform(set_mem_sleep(state), |res| match res {
+                    Ok(state) => cosmic::Action::App(Message::InitMemSleep(Some(state))),
+                    Err(why) => cosmic::Action::App(Message::Errored(why)),
+                });
+            }
+            Message::ToggleWhatIf => {
+                self.what_if_watts = match self.what_if_watts {
+                    Some(_) => None,
+                    // start from the current draw
+                    None => Some(
+                        self.history
+                            .mean_watts(|_| true)
+                            .unwrap_or(10.0)
+                            .clamp(0.0, WHAT_IF_MAX_WATTS)
+                            .round(),
+                    ),
+                };
+            }
+            Message::SetWhatIfWatts(watts) => {
+                self.what_if_watts = Some(watts);
+            }
+            Message::ScreenSaver(true) => {
+                self.screen_off_since = Some(chrono::Local::now());
+            }
+            Message::ScreenSaver(false) => {
+                let Some(since) = self.screen_off_since.take() else {
+                    return Task::none();
+                };
+                if let Some(idle) = self.history.mean_watts(|sample| sample.time >= since) {
+                    let active = self.history.mean_watts(|sample| sample.time < since);
+                    self.screen_off_drain = Some((idle, active));
+                }
+            }
+            Message::PanelAutohide(autohide) => {
+                self.panel_autohide = autohide;
+                // the panel size may have changed with the rest of its config
+                self.update_themed_icon();
+            }
+            Message::StatusNotifier(event) => match event {
+                StatusNotifierEvent::Init(tx) => {
+                    self.status_notifier_sender = Some(tx);
+                    self.update_status_notifier();
+                }
+                StatusNotifierEvent::Activate => {
+                    // without a layer shell popup, settings are the closest fallback
+                    if self.display_server.supports_panel() {
+                        return self.update(Message::TogglePopup);
+                    }
+                    return self.update(Message::OpenSettings);
+                }
+            },
+            Message::Sleep(true) => {
+                self.resume_report = None;
+                return Task::batch([
+                    Task::perform(wakeup_sources(), |sources| {
+                        cosmic::Action::App(Message::WakeupSnapshot(sources))
+                    }),
+                    Task::perform(suspend_then_hibernate(), |res| match res {
+                        Ok(hibernate) => cosmic::Action::App(Message::HibernatePending(hibernate)),
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    }),
+                ]);
+            }
+            Message::HibernatePending(hibernate) => {
+                self.hibernate_pending = hibernate.map(|after| (chrono::Local::now(), after));
+            }
+            Message::Sleep(false) => {
+                self.hibernate_pending = None;
+                let before = std::mem::take(&mut self.wakeup_before);
+                return Task::perform(
+                    async move { resume_report(&before, &wakeup_sources().await).await },
+                    |report| cosmic::Action::App(Message::ResumeReport(report)),
+                );
+            }
+            Message::WakeupSnapshot(sources) => {
+                self.wakeup_before = sources;
+            }
+            Message::ResumeReport(report) => {
+                if report.is_empty() {
+                    return Task::none();
+                }
+                self.resume_report = Some((std::time::Instant::now(), report));
+                return Task::perform(tokio::time::sleep(RESUME_REPORT_TIMEOUT), |_| {
+                    cosmic::Action::App(Message::ResumeReportTimeout)
+                });
+            }
+            Message::DismissResumeReport => {
+                self.resume_report = None;
+            }
+            Message::ResumeReportTimeout => {
+                // a later resume restarts the timeout
+                if self
+                    .resume_report
+                    .as_ref()
+                    .is_none_or(|(shown, _)| shown.elapsed() >= RESUME_REPORT_TIMEOUT)
+                {
+                    self.resume_report = None;
+                }
+            }
+            Message::BatteryDevices(devices) => {
+                self.battery_devices = devices;
+            }
+            Message::RecordedHistory(charges) => {
+                // UPower doesn't record the profile, the current one is the best guess
+                let profile = self.power_profile;
+                self.history.seed(charges.into_iter().map(|charge| Sample {
+                    time: charge.time,
+                    percent: charge.percent,
+                    watts: charge.watts,
+                    state: charge.state,
+                    profile,
+                }));
+            }
+            Message::ToggleBatteryDevice(path) => {
+                if self.expanded_battery_device.as_ref() == Some(&path) {
+                    self.expanded_battery_device = None;
+                } else {
+                    self.expanded_battery_device = Some(path);
+                }
+            }
+            Message::TogglePowerDevices => {
+                if self.power_devices.take().is_none() {
+                    return Task::perform(power_devices(), |res| match res {
+                        Ok(devices) => cosmic::Action::App(Message::PowerDevices(devices)),
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    });
+                }
+                self.selected_power_device = None;
+            }
+            Message::PowerDevices(devices) => {
+                self.power_devices = Some(devices);
+            }
+            Message::SelectPowerDevice(path) => {
+                if self.selected_power_device.as_ref() == Some(&path) {
+                    self.selected_power_device = None;
+                } else {
+                    self.selected_power_device = Some(path);
+                }
+            }
+            Message::ToggleKernelParams => {
+                if self.kernel_params.take().is_none() {
+                    return Task::perform(kernel_params(), |params| {
+                        cosmic::Action::App(Message::KernelParams(params))
+                    });
+                }
+                self.kernel_param_help = None;
+            }
+            Message::KernelParams(params) => {
+                self.kernel_params = Some(params);
+            }
+            Message::KernelParamHelp(key) => {
+                if self.kernel_param_help.as_ref() == Some(&key) {
+                    self.kernel_param_help = None;
+                } else {
+                    self.kernel_param_help = Some(key);
+                }
+            }
+            Message::ToggleUsbDevices => {
+                self.show_usb_devices = !self.show_usb_devices;
+            }
+            Message::TogglePowerQuality => {
+                self.show_power_quality = !self.show_power_quality;
+            }
+            Message::PowerQuality(quality) => {
+                self.power_quality = quality;
+            }
+            Message::UsbDevices(devices) => {
+                self.usb_devices = devices;
+            }
+            Message::SetUsbAutosuspend(id, autosuspend) => {
+                return Task::perform(usb::set_autosuspend(id, autosuspend), |res| match res {
+                    Ok(devices) => cosmic::Action::App(Message::UsbDevices(devices)),
+                    Err(why) => cosmic::Action::App(Message::Errored(why)),
+                });
+            }
+            Message::WakeOnLan(state) => {
+                self.wake_on_lan = state;
+            }
+            Message::SetWakeOnLan(chain, enable) => {
+                let Some(state) = self.wake_on_lan.as_mut() else {
+                    return Task::none();
+                };
+                self.timeline.set_chain(chain).start();
+                state.enabled = enable;
+                return Task::perform(set_wake_on_lan(state.interface.clone(), enable), |res| {
+                    match res {
+                        Ok(state) => cosmic::Action::App(Message::WakeOnLan(Some(state))),
+                        Err(why) => cosmic::Action::App(Message::Errored(why)),
+                    }
+                });
+            }
+            Message::Modem(status) => {
+                self.modem = status;
+            }
+            Message::SetModemEnabled(enable) => {
+                let Some(modem) = &self.modem else {
+                    return Task::none();
+                };
+                return Task::perform(
+                    set_modem_enabled(modem.path.clone(), enable),
+                    |res| match res {
+                        Ok(status) => cosmic::Action::App(Message::Modem(status)),
+                        Err(why) => cosmic::Action::App(Message::Errored(why)),
+                    },
+                );
+            }
+            Message::SetDockedProtection(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.config.docked_battery_protection = enable;
+                self.write_config();
+                if enable {
+                    return Task::perform(is_docked(), |docked| {
+                        cosmic::Action::App(Message::Docked(docked))
+                    });
+                }
+            }
+            Message::SetSmartSleep(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.config.smart_sleep = enable;
+                self.write_config();
+                if !enable {
+                    self.cancel_smart_sleep();
+                }
+            }
+            Message::SetKeepAwake(chain, enable) => {
+                self.timeline.set_chain(chain).start();
+                self.keep_awake = enable;
+                if !enable {
+                    self.inhibitor = None;
+                    return Task::none();
+                }
+                return Task::perform(inhibit_idle(fl!("keep-awake-reason")), |res| match res {
+                    Ok(inhibitor) => cosmic::Action::App(Message::KeepAwake(Some(inhibitor))),
+                    Err(why) => {
+                        tracing::error!("Failed to keep the screen awake: {why}");
+                        cosmic::Action::App(Message::KeepAwake(None))
+                    }
+                });
+            }
+            Message::KeepAwake(inhibitor) => {
+                // switched off again while waiting drops the lock right away
+                if self.keep_awake {
+                    self.keep_awake = inhibitor.is_some();
+                    self.inhibitor = inhibitor;
+                }
+            }
+            Message::InputIdle(idle) => {
+                let now = std::time::Instant::now();
+                self.input_activity
+                    .get_or_insert_with(|| InputActivity::new(now))
+                    .set_idle(idle, now);
+                if !idle {
+                    self.cancel_smart_sleep();
+                    return Task::none();
+                }
+                return self.check_smart_sleep();
+            }
+            Message::SmartSleepTick => {
+                if let Some(activity) = &mut self.input_activity {
+                    activity.sample(std::time::Instant::now());
+                }
+                return self.check_smart_sleep();
+            }
+            Message::SmartSleepTimeout => {
+                let due = self
+                    .smart_sleep
+                    .is_some_and(|(since, _)| since.elapsed() >= smart_sleep::GRACE_PERIOD);
+                if due {
+                    self.cancel_smart_sleep();
+                    if self.on_battery {
+                        return Task::perform(suspend(), |res| match res {
+                            Ok(()) => cosmic::Action::None,
+                            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                        });
+                    }
+                }
+            }
+            Message::CancelSmartSleep => {
+                self.cancel_smart_sleep();
+            }
+            Message::AcStateChanged(online) => {
+                if online {
+                    self.cancel_smart_sleep();
+                }
+                let profile = if online {
+                    &self.config.profile_on_ac
+                } else {
+                    &self.config.profile_on_battery
+                };
+                let Some(name) = profile else {
+                    return Task::none();
+                };
+                let Some(profile) = Power::from_name(name) else {
+                    tracing::warn!("Unknown power profile {name} in config");
+                    return Task::none();
+                };
+                if let Some(tx) = self.power_profile_sender.as_ref() {
+                    let _ = tx.send(PowerProfileRequest::Set(profile));
+                }
+            }
+            Message::Docked(docked) => {
+                return self.update_docked_protection(docked);
+            }
+            Message::ThunderboltDevices(devices) => {
+                self.thunderbolt_devices = devices;
+                self.update_dock_profile();
+            }
+            Message::RememberDock(uuid) => {
+                self.config.dock_uuid = Some(uuid);
+                self.write_config();
+                self.update_dock_profile();
+            }
+            Message::ForgetDock => {
+                self.config.dock_uuid = None;
+                self.write_config();
+                self.update_dock_profile();
+            }
+            Message::EnableStorageMode => {
+                return Task::perform(enable_storage_mode(), |res| match res {
+                    Ok(previous) => cosmic::Action::App(Message::StorageModeEnabled(previous)),
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                });
+            }
+            Message::StorageModeEnabled(previous) => {
+                self.config.storage_mode_restore = Some(previous);
+                self.write_config();
+                self.set_charging_limit(true);
+            }
+            Message::StartCalibration => {
+                let restore_limit = self.charging_limit == Some(true);
+                self.config.calibration = Some(CalibrationStep::Discharge { restore_limit });
+                self.write_config();
+                if restore_limit {
+                    self.set_charging_limit(false);
+                    return Task::perform(clear_charging_limit(), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    });
+                }
+            }
+            Message::CancelCalibration => {
+                if let Some(step) = self.config.calibration.take() {
+                    self.write_config();
+                    return self.restore_calibration_limit(step);
+                }
+            }
+            Message::DismissCalibration => {
+                // asks again after another interval
+                self.config.calibration_dismissed = Some(chrono::Local::now().date_naive());
+                self.write_config();
+            }
+            Message::DisableStorageMode => {
+                if let Some(previous) = self.config.storage_mode_restore.take() {
+                    self.write_config();
+                    return Task::perform(
+                        async move {
+                            set_charge_thresholds(previous).await?;
+                            get_charging_limit().await
+                        },
+                        |res| match res {
+                            Ok(limit) => {
+                                cosmic::Action::App(Message::InitChargingLimit(Some(limit)))
+                            }
+                            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                        },
+                    );
+                }
+            }
+            Message::ConfigChanged(config) => {
+                let diff = self.config.diff(&config);
+                if !diff.is_empty() {
+                    tracing::info!("Config changed: {diff}");
+                }
+                let export = config.gsettings_sync && !diff.is_empty();
+                self.config = config;
+                self.update_themed_icon();
+                self.update_night_light();
+                if export {
+                    return self.export_gsettings();
+                }
+            }
+            Message::ScheduleTick => {
+                let now = chrono::Local::now();
+                if let Some(shutdown_at) = self.shutdown_at {
+                    if now >= shutdown_at {
+                        self.shutdown_at = None;
+                        return Task::perform(power_off(), |res| match res {
+                            Ok(()) => cosmic::Action::None,
+                            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                        });
+                    }
+                    return Task::none();
+                }
+                let Some(slot) = schedule::due(&self.config.shutdown_schedule, now) else {
+                    return Task::none();
+                };
+                // a cancelled countdown isn't restarted for the same slot
+                if self.last_shutdown_slot == Some(slot) {
+                    return Task::none();
+                }
+                self.last_shutdown_slot = Some(slot);
+                self.shutdown_at = Some(now + schedule::COUNTDOWN);
+                return self.send_notification(Notification {
+                    summary: fl!("scheduled-shutdown"),
+                    body: fl!(
+                        "scheduled-shutdown-desc",
+                        minutes = schedule::COUNTDOWN.num_minutes()
+                    ),
+                    icon: "system-shutdown-symbolic".to_string(),
+                    urgency: Urgency::Critical,
+                    resident: false,
+                    expire_timeout: -1,
+                    actions: Vec::new(),
+                });
+            }
+            #[cfg(feature = "energy-goals")]
+            Message::EnergyGoalTick => {
+                return self.update_energy_goal();
+            }
+            Message::TravelTick => {
+                let now = chrono::Local::now();
+                if self.charging_limit != Some(true)
+                    || self.next_travel_check.is_some_and(|next| now < next)
+                {
+                    return Task::none();
+                }
+                self.next_travel_check = Some(now + TRAVEL_CHECK_INTERVAL);
+                return Task::perform(trip_tomorrow(), |trip| {
+                    cosmic::Action::App(Message::TripTomorrow(trip))
+                });
+            }
+            Message::TripTomorrow(trip) => {
+                let (Some(date), Some(conn)) = (trip, self.zbus_connection.clone()) else {
+                    return Task::none();
+                };
+                if self.trip_reminded == Some(date) || self.charging_limit != Some(true) {
+                    return Task::none();
+                }
+                self.trip_reminded = Some(date);
+                return Task::perform(
+                    notifications::send_with_actions(
+                        conn,
+                        notifications::trip_reminder(self.config.charge_limit_threshold),
+                    ),
+                    |res| match res {
+                        Ok(action) => cosmic::Action::App(Message::TripAction(action)),
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    },
+                );
+            }
+            Message::TripAction(action) => match action.as_deref() {
+                Some(notifications::TRIP_CHARGE_FULL) => {
+                    self.set_charging_limit(false);
+                    return Task::perform(clear_charging_limit(), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    });
+                }
+                Some(notifications::TRIP_REMIND_LATER) => {
+                    self.trip_reminded = None;
+                    self.next_travel_check = Some(chrono::Local::now() + TRAVEL_REMIND_LATER);
+                }
+                _ => {}
+            },
+            Message::CancelShutdown => {
+                self.shutdown_at = None;
+            }
+            Message::HealthSample(details, uptime) => {
+                let Some(details) = details else {
+                    return Task::none();
+                };
+                if let Some(temperature) = details.temperature {
+                    self.thermal_history.push(temperature);
+                }
+                self.health = uptime
+                    .and_then(|uptime| HealthScore::new(&details, &self.thermal_history, uptime));
+                let chemistry = chemistry::detect(&details.model, details.technology);
+                self.battery_details = Some(details);
+
+                let (Some(chemistry), Some(conn)) = (chemistry, self.zbus_connection.clone())
+                else {
+                    return Task::none();
+                };
+                if self.chemistry_suggested
+                    || self.config.chemistry_suggestion != ChemistrySuggestion::Pending
+                    || self.charging_limit == Some(true)
+                    || chemistry.thresholds().is_none()
+                {
+                    return Task::none();
+                }
+                self.chemistry_suggested = true;
+                let notification =
+                    notifications::charge_limit_suggestion(chemistry.recommended_limit());
+                return Task::perform(
+                    notifications::send_with_actions(conn, notification),
+                    move |res| match res {
+                        Ok(action) => {
+                            cosmic::Action::App(Message::ChemistryAction(chemistry, action))
+                        }
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                    },
+                );
+            }
+            Message::ChemistryAction(chemistry, action) => {
+                self.chemistry_suggested = false;
+                // closing the notification counts as an answer, so it's only shown once
+                let accepted = action.as_deref() == Some(notifications::SUGGESTION_ACCEPT);
+                self.config.chemistry_suggestion = if accepted {
+                    ChemistrySuggestion::Accepted
+                } else {
+                    ChemistrySuggestion::Dismissed
+                };
+                self.write_config();
+                if let Some(thresholds) = chemistry.thresholds().filter(|_| accepted) {
+                    self.set_charging_limit(true);
+                    return Task::perform(set_charge_thresholds(thresholds), |res| match res {
+                        Ok(()) => cosmic::Action::None,
+                        Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
                     });
                 }
             }
+            Message::IconThemes(themes) => {
+                self.icon_theme_options = std::iter::once(fl!("icon-theme-system"))
+                    .chain(themes)
+                    .collect();
+            }
+            Message::SetIconTheme(index) => {
+                self.config.battery_icon_theme = (index > 0)
+                    .then(|| self.icon_theme_options.get(index).cloned())
+                    .flatten();
+                self.write_config();
+                self.update_themed_icon();
+            }
+            Message::CpuTemperature(temperature) => {
+                return self.update_thermal_mitigation(temperature);
+            }
+            Message::GSettingsChanged(key, value) => {
+                let mut config = self.config.clone();
+                gsettings::apply(&mut config, &key, &value);
+                if config != self.config {
+                    self.config = config;
+                    self.write_config();
+                }
+            }
+            Message::TypicalBatteryLife(life) => {
+                self.typical_battery_life = life;
+            }
+            Message::Fans(fans) => {
+                self.fans = fans;
+            }
+            Message::ExportHistory => {
+                return Task::perform(
+                    history::export_csv(self.history.to_csv(), fl!("export-csv-title")),
+                    |res| match res {
+                        Ok(path) => cosmic::Action::App(Message::HistoryExported(path)),
+                        Err(why) => cosmic::Action::App(Message::Errored(why)),
+                    },
+                );
+            }
+            Message::HistoryExported(path) => {
+                if let Some(path) = path {
+                    tracing::info!("Exported power history to {}", path.display());
+                }
+            }
             Message::Errored(why) => {
                 tracing::error!("{}", why);
             }
@@ -319,6 +2262,14 @@ impl cosmic::Application for CosmicBatteryApplet {
                 self.dragging_kbd_brightness = false;
                 self.dragging_screen_brightness = false;
 
+                self.show_all_options = false;
+                self.expanded_battery_device = None;
+                self.settings_failed = false;
+                self.confirm_session_end = None;
+                self.power_devices = None;
+                self.selected_power_device = None;
+                self.kernel_params = None;
+                self.kernel_param_help = None;
                 if let Some(p) = self.popup.take() {
                     return destroy_popup(p);
                 } else {
@@ -330,40 +2281,155 @@ impl cosmic::Application for CosmicBatteryApplet {
                     let new_id = window::Id::unique();
                     self.popup.replace(new_id);
 
-                    let popup_settings = self.core.applet.get_popup_settings(
+                    let mut popup_settings = self.core.applet.get_popup_settings(
                         self.core.main_window_id().unwrap(),
                         new_id,
                         Some((1, 1)),
                         None,
                         None,
                     );
+                    if self.panel_autohide {
+                        popup_settings.positioner.offset = self.autohide_offset();
+                    }
                     if let Some(tx) = self.power_profile_sender.as_ref() {
                         let _ = tx.send(PowerProfileRequest::Get);
                     }
                     if let Some(tx) = self.update_trigger.as_ref() {
                         let _ = tx.send(());
                     }
-                    let mut tasks = vec![get_popup(popup_settings)];
-                    // Try again every time a popup is opened
-                    if self.charging_limit.is_none() {
-                        tasks.push(Task::perform(get_charging_limit(), |limit| {
-                            cosmic::Action::App(Message::InitChargingLimit(limit.ok()))
-                        }));
-                    }
+                    let mut tasks = vec![
+                        get_popup(popup_settings),
+                        Task::perform(icon_theme::battery_themes(), |themes| {
+                            cosmic::Action::App(Message::IconThemes(themes))
+                        }),
+                        Task::perform(usb::usb_devices(), |devices| {
+                            cosmic::Action::App(Message::UsbDevices(devices))
+                        }),
+                        Task::perform(power_quality(), |quality| {
+                            cosmic::Action::App(Message::PowerQuality(quality))
+                        }),
+                        Task::perform(wake_on_lan(), |res| {
+                            cosmic::Action::App(Message::WakeOnLan(res.ok().flatten()))
+                        }),
+                        Task::perform(modem_status(), |status| {
+                            cosmic::Action::App(Message::Modem(status))
+                        }),
+                        Task::perform(ddc::external_displays(), |displays| {
+                            cosmic::Action::App(Message::ExternalDisplays(displays))
+                        }),
+                    ];
+                    // Read again every time a popup is opened, the thresholds may
+                    // have been changed with system76-power or through sysfs
+                    tasks.push(Task::perform(get_charging_limit(), |limit| {
+                        cosmic::Action::App(Message::InitChargingLimit(limit.ok()))
+                    }));
                     return Task::batch(tasks);
                 }
             }
             Message::UpowerDevice(event) => match event {
-                DeviceDbusEvent::Update {
-                    on_battery,
-                    percent,
-                    time_to_empty,
-                } => {
-                    self.update_battery(percent, on_battery);
-                    self.time_remaining = Duration::from_secs(time_to_empty as u64);
+                DeviceDbusEvent::Update(device) => {
+                    self.no_battery = false;
+                    self.upower_error = None;
+                    self.warning_level = device.warning_level;
+                    // the first update only reports the initial state
+                    let initial = self.history.is_empty();
+                    let ac_changed = device.on_battery != self.on_battery && !initial;
+                    let unplugged = ac_changed && device.on_battery;
+                    let plugged_in = ac_changed && !device.on_battery;
+                    self.update_battery(device.percent, device.on_battery);
+                    self.update_power_saver();
+                    self.state_machine
+                        .transition(PowerState::new(device.state, device.percent));
+                    self.time_remaining = Duration::from_secs(match device.state {
+                        BatteryState::Charging => device.time_to_full.max(0) as u64,
+                        _ if device.on_battery => device.time_to_empty.max(0) as u64,
+                        _ => 0,
+                    });
+                    self.update_status_notifier();
+                    self.energy_totals.push(device.energy, device.on_battery);
+                    self.battery_energy = device.energy;
+                    self.energy_rate = device.energy_rate;
+                    let calibration = self.advance_calibration(device.percent, device.state);
+                    if device.state == BatteryState::Discharging {
+                        self.drain_baseline
+                            .push(chrono::Local::now(), device.energy_rate);
+                        self.unusual_drain = self
+                            .drain_baseline
+                            .unusual(device.energy_rate)
+                            .map(|typical| (device.energy_rate, typical));
+                    } else {
+                        self.unusual_drain = None;
+                    }
+                    self.history.push(Sample {
+                        time: chrono::Local::now(),
+                        percent: device.percent,
+                        watts: device.energy_rate,
+                        state: device.state,
+                        profile: self.power_profile,
+                    });
+                    let mut tasks = vec![
+                        calibration,
+                        self.check_low_battery(),
+                        Task::perform(
+                            async {
+                                (
+                                    battery_details().await.ok().flatten(),
+                                    health::uptime().await,
+                                )
+                            },
+                            |(details, uptime)| {
+                                cosmic::Action::App(Message::HealthSample(details, uptime))
+                            },
+                        ),
+                    ];
+                    if initial {
+                        tasks.push(Task::perform(
+                            charge_history(RECORDED_HISTORY, RECORDED_HISTORY_POINTS),
+                            |res| match res {
+                                Ok(charges) => {
+                                    cosmic::Action::App(Message::RecordedHistory(charges))
+                                }
+                                Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                            },
+                        ));
+                    }
+                    if ac_changed {
+                        tasks.push(cosmic::task::message(Message::AcStateChanged(
+                            !device.on_battery,
+                        )));
+                    }
+                    if unplugged && self.config.notify_on_unplug {
+                        let time = (!self.time_remaining.is_zero())
+                            .then(|| duration_to_natural(self.time_remaining));
+                        tasks.push(self.send_notification(notifications::unplugged(
+                            self.battery_percent,
+                            time,
+                            &self.icon_name,
+                        )));
+                    }
+                    if plugged_in && self.config.notify_on_plug_in {
+                        tasks.push(self.send_notification(notifications::plugged_in(
+                            self.battery_percent,
+                            &self.icon_name,
+                        )));
+                    }
+                    #[cfg(feature = "energy-goals")]
+                    tasks.push(self.update_energy_goal());
+                    if self.config.docked_battery_protection {
+                        tasks.push(Task::perform(is_docked(), |docked| {
+                            cosmic::Action::App(Message::Docked(docked))
+                        }));
+                    }
+                    return Task::batch(tasks);
                 }
                 DeviceDbusEvent::NoBattery => {
-                    std::process::exit(0);
+                    self.no_battery = true;
+                    self.upower_error = None;
+                    self.update_status_notifier();
+                }
+                DeviceDbusEvent::Unreachable(err) => {
+                    self.upower_error = Some(err);
+                    self.update_status_notifier();
                 }
             },
             Message::KeyboardBacklight(event) => match event {
@@ -371,28 +2437,66 @@ impl cosmic::Application for CosmicBatteryApplet {
                     self.kbd_sender = Some(tx);
                 }
                 KeyboardBacklightUpdate::MaxBrightness(max_brightness) => {
-                    self.max_kbd_brightness = Some(max_brightness);
+                    // UPower reports 0 for keyboards without a controllable backlight
+                    self.max_kbd_brightness = Some(max_brightness).filter(|max| *max > 0);
                 }
                 KeyboardBacklightUpdate::Brightness(brightness) => {
                     if !self.dragging_kbd_brightness {
                         self.kbd_brightness = Some(brightness);
+                        self.remember_brightness();
                     }
                 }
             },
             Message::InitProfile(tx, profile) => {
                 self.power_profile_sender.replace(tx);
                 self.power_profile = profile;
+                self.update_dock_profile();
             }
             Message::Profile(profile) => {
+                let switched = profile != self.power_profile;
                 self.power_profile = profile;
                 if let Some(tx) = &self.kbd_sender {
                     let _ = tx.send(KeyboardBacklightRequest::Get);
                 }
+                if switched && self.on_battery {
+                    return Task::perform(sample_energy_rate(PROFILE_SAMPLE_PERIOD), move |res| {
+                        match res {
+                            Ok(watts) => {
+                                cosmic::Action::App(Message::ProfilePowerSample(profile, watts))
+                            }
+                            Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                        }
+                    });
+                }
+            }
+            Message::ProfilePowerSample(profile, watts) => {
+                // switched again while sampling
+                let Some(watts) = watts.filter(|_| profile == self.power_profile) else {
+                    return Task::none();
+                };
+                self.config
+                    .profile_power
+                    .entry(profile.as_str().to_string())
+                    .or_default()
+                    .add((watts * 1000.0).round() as u32);
+                self.write_config();
             }
             Message::SelectProfile(profile) => {
+                // the user's choice wins over thermal mitigation
+                self.thermal_mitigating = false;
+                self.thermal_restore_profile = None;
                 if let Some(tx) = self.power_profile_sender.as_ref() {
                     let _ = tx.send(PowerProfileRequest::Set(profile));
                 }
+                let powersave = self.cpu_governor.as_ref().and_then(|g| {
+                    g.available
+                        .iter()
+                        .position(|governor| governor == "powersave")
+                        .filter(|_| g.current != "powersave")
+                });
+                if let (Power::Battery, Some(index)) = (profile, powersave) {
+                    return self.update(Message::SetCpuGovernor(index));
+                }
             }
             Message::CloseRequested(id) => {
                 self.dragging_kbd_brightness = false;
@@ -409,7 +2513,9 @@ impl cosmic::Application for CosmicBatteryApplet {
                         exec,
                     });
                 } else {
-                    tracing::error!("Wayland tx is None");
+                    // no activation token to be had, start it without one
+                    tracing::warn!("Starting cosmic-settings without an activation token");
+                    return spawn_settings(None);
                 };
             }
             Message::Token(u) => match u {
@@ -420,15 +2526,17 @@ impl cosmic::Application for CosmicBatteryApplet {
                     self.token_tx = None;
                 }
                 TokenUpdate::ActivationToken { token, .. } => {
-                    let mut cmd = std::process::Command::new("cosmic-settings");
-                    cmd.arg("power");
-                    if let Some(token) = token {
-                        cmd.env("XDG_ACTIVATION_TOKEN", &token);
-                        cmd.env("DESKTOP_STARTUP_ID", &token);
-                    }
-                    tokio::spawn(cosmic::process::spawn(cmd));
+                    return spawn_settings(token);
                 }
             },
+            Message::SettingsSpawned(true) => {
+                self.settings_failed = false;
+                return self.close_popup_then(Task::none());
+            }
+            Message::SettingsSpawned(false) => {
+                tracing::error!("Failed to start cosmic-settings");
+                self.settings_failed = true;
+            }
             Message::GpuInit(tx) => {
                 self.update_trigger = Some(tx);
             }
@@ -459,7 +2567,15 @@ impl cosmic::Application for CosmicBatteryApplet {
                 tracing::error!("Failed to connect to session dbus: {}", err);
             }
             Message::ZbusConnection(Ok(conn)) => {
-                self.zbus_connection = Some(conn);
+                self.zbus_connection = Some(conn.clone());
+                return Task::perform(instance::claim(conn), |res| match res {
+                    Ok(true) => cosmic::Action::None,
+                    Ok(false) => {
+                        tracing::info!("Battery applet already running for this panel, exiting");
+                        std::process::exit(0);
+                    }
+                    Err(why) => cosmic::Action::App(Message::Errored(why.to_string())),
+                });
             }
             Message::SettingsDaemon(event) => match event {
                 settings_daemon::Event::Sender(tx) => {
@@ -471,9 +2587,21 @@ impl cosmic::Application for CosmicBatteryApplet {
                 settings_daemon::Event::DisplayBrightness(brightness) => {
                     if !self.dragging_screen_brightness {
                         self.screen_brightness = Some(brightness);
+                        self.remember_brightness();
                     }
                 }
             },
+            Message::Backlight(backlight) => {
+                // the settings daemon's reports take over once it's reached
+                if let Some(backlight) = backlight.as_ref().filter(|_| {
+                    self.settings_daemon_sender.is_none() && !self.dragging_screen_brightness
+                }) {
+                    self.max_screen_brightness = Some(backlight.max);
+                    self.screen_brightness = Some(backlight.brightness);
+                    self.update_display();
+                }
+                self.backlight = backlight;
+            }
             Message::Surface(a) => {
                 return cosmic::task::message(cosmic::Action::Cosmic(
                     cosmic::app::Action::Surface(a),
@@ -484,12 +2612,92 @@ impl cosmic::Application for CosmicBatteryApplet {
     }
 
     fn view(&self) -> Element<Message> {
-        let btn = self
-            .core
-            .applet
-            .icon_button(&self.icon_name)
-            .on_press_down(Message::TogglePopup)
-            .into();
+        if self.tray_mode() == TrayMode::StatusNotifierItem {
+            // the window stays mapped so the popup has a parent to open from
+            return self
+                .core
+                .applet
+                .autosize_window(horizontal_space().width(Length::Fixed(1.0)))
+                .into();
+        }
+
+        let percent = format!("{:.0}%", self.battery_percent);
+        let btn = if self.no_battery {
+            self.core.applet.icon_button(AC_ICON)
+        } else if self.config.panel_display == PanelDisplay::Percentage {
+            button::custom(self.core.applet.text(percent.clone()))
+                .padding(self.core.applet.suggested_padding(true))
+                .class(cosmic::theme::Button::AppletIcon)
+        } else if self.themed_icon.is_some() {
+            self.core
+                .applet
+                .icon_button_from_handle(self.battery_icon())
+        } else {
+            self.core.applet.icon_button(&self.icon_name)
+        }
+        .on_press_down(Message::TogglePopup);
+        let message = self.hibernate_message().or_else(|| {
+            self.upower_error
+                .as_ref()
+                .map(|err| fl!("upower-unreachable", error = err.as_str()))
+        });
+        let btn: Element<_> = match message {
+            Some(message) => self
+                .core
+                .applet
+                .applet_tooltip(btn, message, self.popup.is_some(), Message::Surface, None)
+                .into(),
+            None => btn.into(),
+        };
+        let mut btn = GestureArea::new(btn)
+            .on_vertical_swipe(Message::SwipeScreenBrightness)
+            .on_swipe(Message::Swipe);
+        if self.config.scroll_action == ScrollAction::ScreenBrightness {
+            btn = btn.on_scroll(Message::ScrollScreenBrightness);
+        }
+        let btn: Element<_> = btn.into();
+        // the brightness set by scrolling takes the place of the charge for a moment
+        let label = match self
+            .screen_brightness_percent()
+            .filter(|_| self.scrolled_at.is_some())
+        {
+            Some(brightness) => Some(format!("{:.0}%", brightness * 100.0)),
+            None => (self.config.panel_display == PanelDisplay::IconAndPercentage
+                && !self.no_battery)
+                .then_some(percent),
+        };
+        let btn = match label {
+            Some(label) => {
+                let label = self.core.applet.text(label).into();
+                match self.core.applet.anchor {
+                    PanelAnchor::Left | PanelAnchor::Right => {
+                        Column::with_children(vec![btn, label])
+                            .align_x(Alignment::Center)
+                            .into()
+                    }
+                    PanelAnchor::Top | PanelAnchor::Bottom => Row::with_children(vec![btn, label])
+                        .align_y(Alignment::Center)
+                        .into(),
+                }
+            }
+            None => btn,
+        };
+        let btn = if self.inhibitor.is_some() {
+            let emblem = icon::from_name(KEEP_AWAKE_ICON)
+                .size(12)
+                .symbolic(true)
+                .into();
+            match self.core.applet.anchor {
+                PanelAnchor::Left | PanelAnchor::Right => Column::with_children(vec![btn, emblem])
+                    .align_x(Alignment::Center)
+                    .into(),
+                PanelAnchor::Top | PanelAnchor::Bottom => Row::with_children(vec![btn, emblem])
+                    .align_y(Alignment::Center)
+                    .into(),
+            }
+        } else {
+            btn
+        };
 
         let content = if !self.gpus.is_empty() {
             let dot = container(vertical_space().height(Length::Fixed(0.0)))
@@ -529,111 +2737,976 @@ impl cosmic::Application for CosmicBatteryApplet {
             space_xxs, space_s, ..
         } = theme::active().cosmic().spacing;
 
-        let name = text::body(fl!("battery"));
-        let description = text::caption(
-            if !self.on_battery || self.time_remaining == Duration::from_secs(0u64) {
-                format!("{:.0}%", self.battery_percent)
-            } else {
-                format!(
-                    "{} {} ({:.0}%)",
-                    format_duration(self.time_remaining),
-                    fl!("until-empty"),
-                    self.battery_percent
+        let name = text::body(self.power_source());
+        let description = row![text::caption(self.battery_description())]
+            .push_maybe(self.energy_rate_label().map(text::caption))
+            .spacing(8);
+
+        let condensed = self.is_condensed();
+        let mut content = Vec::new();
+
+        if let Some(level) = self.low_battery_banner() {
+            // fades between 60% and 100% opacity
+            let millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let phase = (millis % PULSE_PERIOD_MS) as f32 / PULSE_PERIOD_MS as f32;
+            let alpha = 0.8 + 0.2 * (phase * std::f32::consts::TAU).cos();
+            content.push(
+                padded_control(
+                    container(text::heading(fl!("low-battery")))
+                        .padding(space_s)
+                        .width(Length::Fill)
+                        .class(cosmic::style::Container::Custom(Box::new(move |theme| {
+                            let cosmic = theme.cosmic();
+                            let (mut color, on) = if level == LowBattery::Warning {
+                                (cosmic.warning_color(), cosmic.warning.on)
+                            } else {
+                                (cosmic.destructive_color(), cosmic.destructive.on)
+                            };
+                            color.alpha *= alpha;
+                            container::Style {
+                                text_color: Some(on.into()),
+                                background: Some(Background::Color(color.into())),
+                                border: Border {
+                                    radius: cosmic.corner_radii.radius_s.into(),
+                                    width: 0.0,
+                                    color: Color::TRANSPARENT,
+                                },
+                                shadow: Shadow::default(),
+                                icon_color: Some(on.into()),
+                            }
+                        }))),
                 )
-            },
-        );
+                .into(),
+            );
+        }
 
-        let mut content = vec![
-            padded_control(
-                row![
-                    icon::from_name(&*self.icon_name).size(24).symbolic(true),
-                    column![name, description]
-                ]
-                .spacing(8)
-                .align_y(Alignment::Center),
+        if self.smart_sleep.is_some() {
+            content.push(
+                padded_control(
+                    column![
+                        text::heading(fl!("smart-sleep-pending")),
+                        text::caption(fl!("smart-sleep-pending-desc")),
+                    ]
+                    .spacing(space_xxs),
+                )
+                .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("smart-sleep-cancel")).width(Length::Fill))
+                    .on_press(Message::CancelSmartSleep)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(message) = self.hibernate_message() {
+            content.push(padded_control(text::body(message)).into());
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some((_, report)) = self.resume_report.as_ref() {
+            let sources = report
+                .iter()
+                .map(|source| {
+                    let kind = match source.kind {
+                        WakeKind::Usb => fl!("wake-usb"),
+                        WakeKind::Network => fl!("wake-network"),
+                        WakeKind::Timer => fl!("wake-timer"),
+                        WakeKind::Lid => fl!("wake-lid"),
+                        WakeKind::PowerButton => fl!("wake-power-button"),
+                        WakeKind::Other => fl!("wake-other"),
+                    };
+                    format!("{kind} ({})", source.device)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut report_column = column![
+                text::heading(fl!("resume-report")),
+                text::body(fl!("woke-from", sources = sources)),
+            ]
+            .spacing(space_xxs);
+            for source in report.iter().filter(|source| source.drain) {
+                report_column = report_column.push(text::caption(fl!(
+                    "wake-drain",
+                    device = source.device.as_str()
+                )));
+            }
+            content.push(
+                menu_button(report_column.width(Length::Fill))
+                    .on_press(Message::DismissResumeReport)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(shutdown_at) = self.shutdown_at {
+            // round up, so the last minute still reads as 1
+            let seconds = (shutdown_at - chrono::Local::now()).num_seconds().max(0);
+            content.push(
+                padded_control(text::heading(fl!(
+                    "scheduled-shutdown-in",
+                    minutes = (seconds + 59) / 60
+                )))
+                .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("cancel-shutdown")).width(Length::Fill))
+                    .on_press(Message::CancelShutdown)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if self.config.storage_mode_restore.is_some() {
+            content.push(
+                padded_control(
+                    container(
+                        column![
+                            text::heading(fl!("storage-mode-active")),
+                            text::caption(fl!("storage-mode-active-desc")),
+                        ]
+                        .spacing(space_xxs),
+                    )
+                    .padding(space_s)
+                    .width(Length::Fill)
+                    .class(cosmic::style::Container::Custom(Box::new(|theme| {
+                        container::Style {
+                            text_color: Some(theme.cosmic().warning.on.into()),
+                            background: Some(Background::Color(
+                                theme.cosmic().warning_color().into(),
+                            )),
+                            border: Border {
+                                radius: theme.cosmic().corner_radii.radius_s.into(),
+                                width: 0.0,
+                                color: Color::TRANSPARENT,
+                            },
+                            shadow: Shadow::default(),
+                            icon_color: Some(theme.cosmic().warning.on.into()),
+                        }
+                    }))),
+                )
+                .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("storage-mode-disable")).width(Length::Fill))
+                    .on_press(Message::DisableStorageMode)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(step) = self.config.calibration {
+            // each step fills half of the bar
+            let (description, progress) = match step {
+                CalibrationStep::Discharge { .. } => (
+                    fl!("calibration-discharge"),
+                    (100.0 - self.battery_percent) / (100.0 - CALIBRATION_EMPTY_PERCENT) * 50.0,
+                ),
+                CalibrationStep::Charge { .. } => {
+                    (fl!("calibration-charge"), 50.0 + self.battery_percent / 2.0)
+                }
+            };
+            content.push(
+                padded_control(
+                    column![
+                        text::heading(fl!("calibration-running")),
+                        text::caption(description),
+                        progress_bar(0.0..=100.0, progress.clamp(0.0, 100.0) as f32).height(4),
+                    ]
+                    .spacing(space_xxs),
+                )
+                .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("calibration-cancel")).width(Length::Fill))
+                    .on_press(Message::CancelCalibration)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        } else if self.calibration_due() {
+            content.push(
+                padded_control(
+                    column![
+                        text::heading(fl!("calibration-recommended")),
+                        text::caption(fl!("calibration-recommended-desc")),
+                    ]
+                    .spacing(space_xxs),
+                )
+                .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("calibration-start")).width(Length::Fill))
+                    .on_press(Message::StartCalibration)
+                    .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("calibration-dismiss")).width(Length::Fill))
+                    .on_press(Message::DismissCalibration)
+                    .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        let health_badge = self.health.map(|score| {
+            let rating = score.rating();
+            let label = match rating {
+                Rating::Good => fl!("health-good"),
+                Rating::Fair => fl!("health-fair"),
+                Rating::Poor => fl!("health-poor"),
+            };
+            let badge = container(text::caption(format!("{:.0} {label}", score.total())))
+                .padding([2, 8])
+                .class(cosmic::style::Container::Custom(Box::new(move |theme| {
+                    let cosmic = theme.cosmic();
+                    let color = match rating {
+                        Rating::Good => cosmic.success_color(),
+                        Rating::Fair => cosmic.warning_color(),
+                        Rating::Poor => cosmic.destructive_color(),
+                    };
+                    container::Style {
+                        text_color: Some(Color::WHITE),
+                        background: Some(Background::Color(color.into())),
+                        border: Border {
+                            radius: cosmic.corner_radii.radius_xl.into(),
+                            width: 0.0,
+                            color: Color::TRANSPARENT,
+                        },
+                        shadow: Shadow::default(),
+                        icon_color: Some(Color::WHITE),
+                    }
+                })));
+            tooltip(
+                badge,
+                text::caption(fl!(
+                    "health-tooltip",
+                    battery = format!("{:.0}", score.battery),
+                    thermal = format!("{:.0}", score.thermal),
+                    uptime = format!("{:.0}", score.uptime)
+                )),
+                tooltip::Position::Bottom,
             )
-            .into(),
+        });
+
+        if self.no_battery {
+            content.push(
+                padded_control(
+                    row![
+                        icon::from_name(AC_ICON).size(24).symbolic(true),
+                        text::body(fl!("no-battery")),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .into(),
+            );
+        } else {
+            content.extend([
+                padded_control(
+                    row![
+                        battery_ring(
+                            icon::from_name(&*self.icon_name).size(24).symbolic(true),
+                            self.battery_percent
+                        ),
+                        column![name, description]
+                            .push_maybe(
+                                self.battery_details
+                                    .as_ref()
+                                    .and_then(|details| details.firmware_version.as_deref())
+                                    .map(|version| text::caption(fl!(
+                                        "battery-firmware",
+                                        version = version
+                                    )))
+                            )
+                            .push_maybe(
+                                self.battery_details
+                                    .as_ref()
+                                    .and_then(|details| details.capacity)
+                                    .map(|health| {
+                                        let label = text::caption(fl!(
+                                            "battery-health",
+                                            percent = format!("{health:.0}")
+                                        ));
+                                        if self.low_health() {
+                                            label.class(cosmic::style::Text::Custom(|theme| {
+                                                cosmic::iced::widget::text::Style {
+                                                    color: Some(
+                                                        theme.cosmic().warning_color().into(),
+                                                    ),
+                                                }
+                                            }))
+                                        } else {
+                                            label
+                                        }
+                                    })
+                            )
+                            .push_maybe(
+                                (self.low_health() && self.charging_limit == Some(false))
+                                    .then(|| text::caption(fl!("battery-health-suggestion")))
+                            )
+                            .push_maybe(self.battery_details.as_ref().and_then(|details| {
+                                let voltage = details.voltage?;
+                                let warning = details.low_voltage().then(|| {
+                                    tooltip(
+                                        icon::from_name("dialog-warning-symbolic")
+                                            .size(14)
+                                            .symbolic(true),
+                                        text::caption(fl!("battery-voltage-low")),
+                                        tooltip::Position::Bottom,
+                                    )
+                                });
+                                Some(
+                                    row![text::caption(fl!(
+                                        "battery-voltage",
+                                        voltage = format!("{voltage:.1}")
+                                    ))]
+                                    .push_maybe(warning)
+                                    .spacing(space_xxs)
+                                    .align_y(Alignment::Center),
+                                )
+                            })),
+                        horizontal_space(),
+                    ]
+                    .push_maybe(health_badge)
+                    .push_maybe(self.can_lock.then(|| {
+                        tooltip(
+                            button::icon(icon::from_name("system-lock-screen-symbolic"))
+                                .on_press(Message::Lock),
+                            text::caption(fl!("lock-screen")),
+                            tooltip::Position::Bottom,
+                        )
+                    }))
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .into(),
+                padded_control(self.battery_widget.view()).into(),
+            ]);
+        }
+        if let Some((yours, typical)) = self.battery_life_comparison() {
+            let percent = yours.as_secs_f64() / typical.as_secs_f64() * 100.0;
+            content.push(
+                padded_control(text::caption(fl!(
+                    "similar-hardware",
+                    yours = duration_to_natural(yours),
+                    typical = duration_to_natural(typical),
+                    percent = format!("{percent:.0}")
+                )))
+                .into(),
+            );
+        }
+        for device in &self.battery_devices {
+            let expanded = self.expanded_battery_device.as_ref() == Some(&device.path);
+            let kind = battery_device_kind(device.kind);
+            content.push(
+                menu_button(
+                    row![
+                        icon::from_name(battery_device_icon(device.kind))
+                            .size(16)
+                            .symbolic(true),
+                        text::body(if device.name.is_empty() {
+                            kind.clone()
+                        } else {
+                            device.name.clone()
+                        })
+                        .width(Length::Fill),
+                        text::body(format!("{:.0}%", device.percent)),
+                        container(
+                            icon::from_name(if expanded {
+                                "go-down-symbolic"
+                            } else {
+                                "go-up-symbolic"
+                            })
+                            .size(14)
+                            .symbolic(true)
+                        )
+                        .center(Length::Fixed(24.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::ToggleBatteryDevice(device.path.clone()))
+                .into(),
+            );
+            if expanded {
+                let state = match device.state {
+                    BatteryState::Charging => Some(fl!("device-charging")),
+                    BatteryState::Discharging => Some(fl!("device-discharging")),
+                    BatteryState::FullyCharged => Some(fl!("fully-charged")),
+                    _ => None,
+                };
+                content.push(
+                    padded_control(
+                        column![text::caption(kind)]
+                            .push_maybe(state.map(text::caption))
+                            .spacing(space_xxs),
+                    )
+                    .into(),
+                );
+            }
+        }
+        if let Some((current, typical)) = self.unusual_drain {
+            content.push(
+                padded_control(
+                    row![
+                        icon::from_name("dialog-warning-symbolic")
+                            .size(16)
+                            .symbolic(true),
+                        text::body(fl!(
+                            "unusual-drain",
+                            current = format!("{current:.0}"),
+                            typical = format!("{typical:.0}")
+                        ))
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .into(),
+            );
+        }
+        content.push(
             padded_control(divider::horizontal::default())
                 .padding([space_xxs, space_s])
                 .into(),
-            menu_button(
-                row![
+        );
+
+        if condensed {
+            content.extend([
+                padded_control(text::heading(fl!("plug-in-reminder"))).into(),
+                menu_button(text::body(fl!("suspend")).width(Length::Fill))
+                    .on_press(Message::Suspend)
+                    .into(),
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            ]);
+        } else {
+            content.extend([
+                menu_button(
+                    row![
+                        column![
+                            text::body(self.profile_name(Power::Battery)),
+                            text::caption(fl!("battery-desc"))
+                        ]
+                        .width(Length::Fill),
+                        if matches!(self.power_profile, Power::Battery) {
+                            container(
+                                icon::from_name("emblem-ok-symbolic")
+                                    .size(12)
+                                    .symbolic(true),
+                            )
+                        } else {
+                            container(horizontal_space().width(1.0))
+                        }
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::SelectProfile(Power::Battery))
+                .into(),
+                menu_button(
+                    row![
+                        column![
+                            text::body(fl!("balanced")),
+                            text::caption(fl!("balanced-desc"))
+                        ]
+                        .width(Length::Fill),
+                        if matches!(self.power_profile, Power::Balanced) {
+                            container(
+                                icon::from_name("emblem-ok-symbolic")
+                                    .size(12)
+                                    .symbolic(true),
+                            )
+                        } else {
+                            container(horizontal_space().width(1.0))
+                        }
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::SelectProfile(Power::Balanced))
+                .into(),
+                menu_button(
+                    row![
+                        column![
+                            text::body(self.profile_name(Power::Performance)),
+                            text::caption(fl!("performance-desc"))
+                        ]
+                        .width(Length::Fill),
+                        if matches!(self.power_profile, Power::Performance) {
+                            container(
+                                icon::from_name("emblem-ok-symbolic")
+                                    .size(12)
+                                    .symbolic(true),
+                            )
+                        } else {
+                            container(horizontal_space().width(1.0))
+                        }
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::SelectProfile(Power::Performance))
+                .into(),
+            ]);
+
+            if let Some(uuid) = &self.config.dock_uuid {
+                let name = self
+                    .thunderbolt_devices
+                    .iter()
+                    .find(|device| &device.uuid == uuid)
+                    .map_or_else(|| fl!("dock-disconnected"), |device| device.name.clone());
+                content.push(
+                    menu_button(
+                        column![
+                            text::body(fl!("dock-forget")),
+                            text::caption(fl!("dock-remembered", name = name))
+                        ]
+                        .width(Length::Fill),
+                    )
+                    .on_press(Message::ForgetDock)
+                    .into(),
+                );
+            } else {
+                for device in self
+                    .thunderbolt_devices
+                    .iter()
+                    .filter(|d| !d.uuid.is_empty())
+                {
+                    content.push(
+                        menu_button(
+                            column![
+                                text::body(fl!("dock-remember", name = device.name.as_str())),
+                                text::caption(fl!("dock-remember-desc"))
+                            ]
+                            .width(Length::Fill),
+                        )
+                        .on_press(Message::RememberDock(device.uuid.clone()))
+                        .into(),
+                    );
+                }
+            }
+
+            if let Some(governor) = &self.cpu_governor {
+                let label = text::body(fl!("cpu-governor", governor = governor.current.as_str()));
+                let mut row = row![label, horizontal_space()]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                if governor.available.len() > 1 {
+                    let selected = governor
+                        .available
+                        .iter()
+                        .position(|g| *g == governor.current);
+                    row = row.push(dropdown(
+                        &governor.available,
+                        selected,
+                        Message::SetCpuGovernor,
+                    ));
+                }
+                content.push(padded_control(row).into());
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(charging_limit) = self
+            .charging_limit
+            .filter(|_| self.config.storage_mode_restore.is_none() && !condensed)
+        {
+            content.push(
+                padded_control(
+                    anim!(
+                        //toggler
+                        MAX_CHARGE,
+                        &self.timeline,
+                        fl!("max-charge", percent = self.config.charge_limit_threshold),
+                        charging_limit,
+                        Message::SetChargingLimit,
+                    )
+                    .text_size(14)
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+            // only adjustable while the limit is on, so it's clear it needs enabling
+            if charging_limit {
+                content.push(
+                    padded_control(
+                        row![
+                            text::body(fl!("charge-limit-threshold")).width(Length::Fill),
+                            spin_button(
+                                format!("{}%", self.config.charge_limit_threshold),
+                                self.config.charge_limit_threshold,
+                                5,
+                                50,
+                                100,
+                                Message::SetChargeLimitThreshold,
+                            ),
+                        ]
+                        .align_y(Alignment::Center),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(
+                    anim!(
+                        DOCKED_PROTECTION,
+                        &self.timeline,
+                        fl!(
+                            "docked-protection",
+                            percent = self.config.charge_limit_threshold
+                        ),
+                        self.config.docked_battery_protection,
+                        Message::SetDockedProtection,
+                    )
+                    .text_size(14)
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+            content.push(
+                menu_button(
                     column![
-                        text::body(fl!("battery")),
-                        text::caption(fl!("battery-desc"))
+                        text::body(fl!("storage-mode")),
+                        text::caption(fl!("storage-mode-desc"))
                     ]
                     .width(Length::Fill),
-                    if matches!(self.power_profile, Power::Battery) {
-                        container(
-                            icon::from_name("emblem-ok-symbolic")
-                                .size(12)
-                                .symbolic(true),
-                        )
-                    } else {
-                        container(horizontal_space().width(1.0))
-                    }
-                ]
-                .align_y(Alignment::Center),
-            )
-            .on_press(Message::SelectProfile(Power::Battery))
-            .into(),
-            menu_button(
-                row![
+                )
+                .on_press(Message::EnableStorageMode)
+                .into(),
+            );
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(mem_sleep) = self
+            .mem_sleep
+            .as_ref()
+            .filter(|state| state.available.len() > 1 && !condensed)
+        {
+            content.push(
+                padded_control(
                     column![
-                        text::body(fl!("balanced")),
-                        text::caption(fl!("balanced-desc"))
+                        text::body(fl!("suspend-mode")),
+                        text::caption(fl!("suspend-mode-desc"))
                     ]
                     .width(Length::Fill),
-                    if matches!(self.power_profile, Power::Balanced) {
+                )
+                .into(),
+            );
+            for state in &mem_sleep.available {
+                let (name, desc) = match state {
+                    MemSleep::S2Idle => (fl!("suspend-s2idle"), fl!("suspend-s2idle-desc")),
+                    MemSleep::Shallow => (fl!("suspend-shallow"), fl!("suspend-shallow-desc")),
+                    MemSleep::Deep => (fl!("suspend-deep"), fl!("suspend-deep-desc")),
+                };
+                content.push(
+                    menu_button(
+                        row![
+                            column![text::body(name), text::caption(desc)].width(Length::Fill),
+                            if mem_sleep.current == *state {
+                                container(
+                                    icon::from_name("emblem-ok-symbolic")
+                                        .size(12)
+                                        .symbolic(true),
+                                )
+                            } else {
+                                container(horizontal_space().width(1.0))
+                            }
+                        ]
+                        .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::SetMemSleep(*state))
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(state) = self.wake_on_lan.as_ref().filter(|_| !condensed) {
+            content.push(
+                padded_control(
+                    anim!(
+                        WAKE_ON_LAN,
+                        &self.timeline,
+                        fl!("wake-on-lan"),
+                        state.enabled,
+                        Message::SetWakeOnLan,
+                    )
+                    .text_size(14)
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+        }
+
+        if let Some(modem) = self.modem.as_ref().filter(|_| !condensed) {
+            let state = match modem.state {
+                ModemState::Disabled => fl!("modem-disabled"),
+                ModemState::Searching => fl!("modem-searching"),
+                ModemState::Registered => fl!("modem-registered"),
+                ModemState::Connected => fl!("modem-connected"),
+            };
+            let status = match modem.technology.filter(|_| modem.enabled()) {
+                Some(technology) => fl!(
+                    "modem-status",
+                    technology = technology,
+                    state = state,
+                    bars = modem.bars
+                ),
+                None => fl!("modem-status-plain", state = state),
+            };
+            let (action, action_desc) = if modem.enabled() {
+                (fl!("modem-disable"), fl!("modem-disable-desc"))
+            } else {
+                (fl!("modem-enable"), fl!("modem-enable-desc"))
+            };
+            content.push(padded_control(text::body(status)).into());
+            content.push(
+                menu_button(
+                    column![text::body(action), text::caption(action_desc)].width(Length::Fill),
+                )
+                .on_press(Message::SetModemEnabled(!modem.enabled()))
+                .into(),
+            );
+        }
+
+        if !self.usb_devices.is_empty() && !condensed {
+            content.push(
+                menu_button(
+                    row![
+                        text::body(fl!("usb-power")).width(Length::Fill),
                         container(
-                            icon::from_name("emblem-ok-symbolic")
-                                .size(12)
-                                .symbolic(true),
+                            icon::from_name(if self.show_usb_devices {
+                                "go-down-symbolic"
+                            } else {
+                                "go-up-symbolic"
+                            })
+                            .size(14)
+                            .symbolic(true)
                         )
-                    } else {
-                        container(horizontal_space().width(1.0))
-                    }
-                ]
-                .align_y(Alignment::Center),
-            )
-            .on_press(Message::SelectProfile(Power::Balanced))
-            .into(),
-            menu_button(
-                row![
-                    column![
-                        text::body(fl!("performance")),
-                        text::caption(fl!("performance-desc"))
+                        .center(Length::Fixed(24.0)),
                     ]
-                    .width(Length::Fill),
-                    if matches!(self.power_profile, Power::Performance) {
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::ToggleUsbDevices)
+                .into(),
+            );
+            if self.show_usb_devices {
+                for device in &self.usb_devices {
+                    let id = device.id.clone();
+                    content.push(
+                        padded_control(
+                            row![
+                                column![
+                                    text::body(device.product.as_str()),
+                                    text::caption(if device.autosuspend {
+                                        fl!("usb-power-auto")
+                                    } else {
+                                        fl!("usb-power-on")
+                                    })
+                                ]
+                                .width(Length::Fill),
+                                toggler(device.autosuspend).on_toggle(move |autosuspend| {
+                                    Message::SetUsbAutosuspend(id.clone(), autosuspend)
+                                }),
+                            ]
+                            .align_y(Alignment::Center),
+                        )
+                        .into(),
+                    );
+                }
+                let suspended = self.usb_devices.iter().filter(|d| d.autosuspend).count();
+                if suspended > 1 {
+                    let milliwatts = suspended as u32 * usb::AUTOSUSPEND_SAVING_MW;
+                    content.push(
+                        padded_control(text::caption(fl!(
+                            "usb-power-saved",
+                            count = suspended,
+                            watts = format!("{:.1}", f64::from(milliwatts) / 1000.0)
+                        )))
+                        .into(),
+                    );
+                }
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(quality) = self.power_quality.as_ref().filter(|_| !condensed) {
+            content.push(
+                menu_button(
+                    row![
+                        text::body(fl!("power-quality")).width(Length::Fill),
                         container(
-                            icon::from_name("emblem-ok-symbolic")
-                                .size(12)
-                                .symbolic(true),
+                            icon::from_name(if self.show_power_quality {
+                                "go-down-symbolic"
+                            } else {
+                                "go-up-symbolic"
+                            })
+                            .size(14)
+                            .symbolic(true)
                         )
-                    } else {
-                        container(horizontal_space().width(1.0))
-                    }
-                ]
-                .align_y(Alignment::Center),
+                        .center(Length::Fixed(24.0)),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::TogglePowerQuality)
+                .into(),
+            );
+            if self.show_power_quality {
+                let unknown = || fl!("power-quality-unknown");
+                content.push(
+                    padded_control(
+                        column![
+                            text::caption(fl!("power-quality-ups", name = quality.ups.as_str())),
+                            text::body(fl!(
+                                "power-quality-voltage",
+                                voltage = quality
+                                    .input_voltage
+                                    .map_or_else(unknown, |v| format!("{v:.0} V"))
+                            )),
+                            text::body(fl!(
+                                "power-quality-frequency",
+                                frequency = quality
+                                    .input_frequency
+                                    .map_or_else(unknown, |f| format!("{f:.1} Hz"))
+                            )),
+                        ]
+                        .spacing(space_xxs),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        content.push(
+            padded_control(
+                anim!(
+                    KEEP_AWAKE,
+                    &self.timeline,
+                    fl!("keep-awake"),
+                    self.keep_awake,
+                    Message::SetKeepAwake,
+                )
+                .text_size(14)
+                .width(Length::Fill),
             )
-            .on_press(Message::SelectProfile(Power::Performance))
             .into(),
-            padded_control(divider::horizontal::default())
-                .padding([space_xxs, space_s])
-                .into(),
-        ];
+        );
 
-        if let Some(charging_limit) = self.charging_limit {
+        if self.idle_config_helper.is_some() && !condensed {
+            let screen_off = self.idle_config.screen_off_minutes();
+            content.push(
+                padded_control(
+                    row![
+                        text::body(fl!("screen-timeout")).width(Length::Fill),
+                        dropdown(
+                            &self.screen_timeout_options,
+                            SCREEN_TIMEOUT_PRESETS.iter().position(|m| *m == screen_off),
+                            Message::SetScreenTimeout,
+                        ),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .into(),
+            );
+            let delays = self.idle_config.suspend_delays();
+            content.push(
+                padded_control(
+                    column![
+                        text::body(fl!("suspend-idle")),
+                        text::caption(fl!("suspend-idle-desc"))
+                    ]
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+            let rows: [(String, u32, fn(u32) -> Message); 2] = [
+                (
+                    fl!("suspend-idle-ac"),
+                    delays.ac,
+                    Message::SetAcSuspendDelay,
+                ),
+                (
+                    fl!("suspend-idle-battery"),
+                    delays.battery,
+                    Message::SetBatterySuspendDelay,
+                ),
+            ];
+            for (label, minutes, on_change) in rows {
+                content.push(
+                    padded_control(
+                        row![
+                            text::body(label).width(Length::Fill),
+                            spin_button(
+                                fl!("minutes-value", minutes = minutes),
+                                minutes,
+                                5,
+                                0,
+                                240,
+                                on_change,
+                            ),
+                        ]
+                        .align_y(Alignment::Center),
+                    )
+                    .into(),
+                );
+            }
             content.push(
                 padded_control(
                     anim!(
-                        //toggler
-                        MAX_CHARGE,
+                        SMART_SLEEP,
                         &self.timeline,
-                        fl!("max-charge"),
-                        charging_limit,
-                        Message::SetChargingLimit,
+                        fl!("smart-sleep"),
+                        self.config.smart_sleep,
+                        Message::SetSmartSleep,
                     )
                     .text_size(14)
                     .width(Length::Fill),
@@ -647,7 +3720,31 @@ impl cosmic::Application for CosmicBatteryApplet {
             );
         }
 
-        if let Some(max_screen_brightness) = self.max_screen_brightness {
+        if !self.fans.is_empty() && !condensed {
+            for (i, fan) in self.fans.iter().enumerate() {
+                let label = fan
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| fl!("fan", number = i + 1));
+                content.push(
+                    padded_control(
+                        row![
+                            text::body(label).width(Length::Fill),
+                            text::body(fl!("fan-rpm", rpm = fan.rpm)),
+                        ]
+                        .align_y(Alignment::Center),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if let Some(max_screen_brightness) = self.max_screen_brightness.filter(|_| !condensed) {
             if let Some(screen_brightness) = self.screen_brightness {
                 content.push(
                     padded_control(
@@ -678,7 +3775,57 @@ impl cosmic::Application for CosmicBatteryApplet {
             }
         }
 
-        if let Some(max_kbd_brightness) = self.max_kbd_brightness {
+        if !condensed {
+            for (index, display) in self.external_displays.iter().enumerate() {
+                content.push(
+                    padded_control(
+                        column![
+                            text::caption(display.name.as_str()),
+                            row![
+                                icon::from_name("video-display-symbolic")
+                                    .size(24)
+                                    .symbolic(true),
+                                slider(0..=display.max, display.brightness, move |brightness| {
+                                    Message::SetExternalBrightness(index, brightness)
+                                })
+                                .on_release(Message::ReleaseExternalBrightness(index)),
+                                container(
+                                    text(format!(
+                                        "{:.0}%",
+                                        100. * f64::from(display.brightness)
+                                            / f64::from(display.max)
+                                    ))
+                                    .size(16)
+                                )
+                                .width(Length::Fixed(40.0))
+                                .align_x(Alignment::End)
+                            ]
+                            .spacing(12)
+                        ]
+                        .spacing(space_xxs),
+                    )
+                    .into(),
+                );
+            }
+            if !self.external_displays.is_empty() {
+                content.push(
+                    padded_control(
+                        anim!(
+                            LINK_DISPLAYS,
+                            &self.timeline,
+                            fl!("link-displays"),
+                            self.config.link_displays,
+                            Message::SetLinkDisplays,
+                        )
+                        .text_size(14)
+                        .width(Length::Fill),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        if let Some(max_kbd_brightness) = self.max_kbd_brightness.filter(|_| !condensed) {
             if let Some(kbd_brightness) = self.kbd_brightness {
                 content.push(
                     padded_control(
@@ -709,6 +3856,62 @@ impl cosmic::Application for CosmicBatteryApplet {
             }
         }
 
+        if !condensed {
+            content.push(
+                padded_control(
+                    anim!(
+                        NIGHT_LIGHT,
+                        &self.timeline,
+                        fl!("night-light"),
+                        self.config.night_light,
+                        Message::SetNightLight,
+                    )
+                    .text_size(14)
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+            if self.config.night_light || self.config.night_light_automatic {
+                content.push(
+                    padded_control(
+                        row![
+                            icon::from_name("night-light-symbolic")
+                                .size(24)
+                                .symbolic(true),
+                            slider(
+                                gamma::MIN_TEMPERATURE..=gamma::MAX_TEMPERATURE,
+                                self.config.night_light_temperature,
+                                Message::SetNightLightTemperature
+                            )
+                            .step(100u32)
+                            .on_release(Message::ReleaseNightLightTemperature),
+                            container(
+                                text(format!("{} K", self.config.night_light_temperature)).size(16)
+                            )
+                            .width(Length::Fixed(56.0))
+                            .align_x(Alignment::End)
+                        ]
+                        .spacing(12),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(
+                    anim!(
+                        NIGHT_LIGHT_AUTOMATIC,
+                        &self.timeline,
+                        fl!("night-light-automatic"),
+                        self.config.night_light_automatic,
+                        Message::SetNightLightAutomatic,
+                    )
+                    .text_size(14)
+                    .width(Length::Fill),
+                )
+                .into(),
+            );
+        }
+
         content.push(
             padded_control(divider::horizontal::default())
                 .padding([space_xxs, space_s])
@@ -831,11 +4034,324 @@ impl cosmic::Application for CosmicBatteryApplet {
             );
         }
 
+        if (self.energy_totals.used > 0.0 || self.energy_totals.wall > 0.0) && !condensed {
+            let mut energy = column![text::body(fl!(
+                "energy-used",
+                energy = format!("{:.1}", self.energy_totals.used)
+            ))];
+            if self.energy_totals.wall > 0.0 {
+                energy = energy.push(text::caption(fl!(
+                    "energy-wall",
+                    energy = format!("{:.1}", self.energy_totals.wall)
+                )));
+            }
+            #[cfg(feature = "energy-goals")]
+            if self.config.daily_energy_budget > 0 {
+                energy = energy.push(text::caption(fl!(
+                    "energy-goal",
+                    used = format!("{:.1}", self.energy_goal.used),
+                    budget = self.config.daily_energy_budget
+                )));
+            }
+            content.push(padded_control(energy).into());
+        }
+
+        if let Some((idle, active)) = self.screen_off_drain.filter(|_| !condensed) {
+            let mut drain =
+                column![text::body(fl!(
+                    "screen-off-drain",
+                    watts = format!("{idle:.1}")
+                ))]
+                .push_maybe(active.map(|active| {
+                    text::caption(fl!("active-drain", watts = format!("{active:.1}")))
+                }));
+            if idle > HIGH_IDLE_DRAIN_WATTS {
+                drain = drain.push(text::caption(fl!("high-idle-drain")));
+            }
+            content.push(padded_control(drain).into());
+        }
+
+        // only offer the choice when there's another theme to pick
+        if self.icon_theme_options.len() > 1 && !condensed {
+            let selected = match &self.config.battery_icon_theme {
+                Some(theme) => self.icon_theme_options.iter().position(|t| t == theme),
+                None => Some(0),
+            };
+            content.push(
+                padded_control(
+                    row![
+                        icon(self.battery_icon()).size(24),
+                        text::body(fl!("icon-theme")),
+                        horizontal_space(),
+                        dropdown(&self.icon_theme_options, selected, Message::SetIconTheme),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .into(),
+            );
+        }
+
+        if !condensed {
+            content.push(
+                menu_button(
+                    row![
+                        text::body(fl!("power-devices")).width(Length::Fill),
+                        container(
+                            icon::from_name(if self.power_devices.is_some() {
+                                "go-down-symbolic"
+                            } else {
+                                "go-up-symbolic"
+                            })
+                            .size(14)
+                            .symbolic(true)
+                        )
+                        .center(Length::Fixed(24.0)),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::TogglePowerDevices)
+                .into(),
+            );
+        }
+        if let Some(devices) = self.power_devices.as_ref().filter(|_| !condensed) {
+            content.push(padded_control(text::caption(fl!("upower-devices"))).into());
+            for device in devices {
+                content.push(
+                    menu_button(
+                        row![
+                            horizontal_space().width(Length::Fixed(space_s.into())),
+                            text::body(device.kind.as_str()).width(Length::Fill),
+                            text::caption(device.state.as_str()),
+                            text::caption(format!("{:.0}%", device.percent)),
+                        ]
+                        .spacing(space_xxs)
+                        .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::SelectPowerDevice(device.path.clone()))
+                    .into(),
+                );
+                if self.selected_power_device.as_ref() != Some(&device.path) {
+                    continue;
+                }
+                let properties = device
+                    .properties
+                    .iter()
+                    .map(|(name, value)| text::caption(format!("{name}: {value}")).into());
+                content.push(
+                    padded_control(
+                        scrollable(
+                            Column::with_children(
+                                std::iter::once(text::caption(device.path.as_str()).into())
+                                    .chain(properties),
+                            )
+                            .spacing(space_xxs),
+                        )
+                        .height(Length::Fixed(200.0)),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if !condensed {
+            content.push(
+                menu_button(
+                    row![
+                        text::body(fl!("kernel-params")).width(Length::Fill),
+                        container(
+                            icon::from_name(if self.kernel_params.is_some() {
+                                "go-down-symbolic"
+                            } else {
+                                "go-up-symbolic"
+                            })
+                            .size(14)
+                            .symbolic(true)
+                        )
+                        .center(Length::Fixed(24.0)),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::ToggleKernelParams)
+                .into(),
+            );
+        }
+        if let Some(params) = self.kernel_params.as_ref().filter(|_| !condensed) {
+            if params.is_empty() {
+                content.push(padded_control(text::caption(fl!("kernel-params-none"))).into());
+            }
+            for param in params {
+                let key = param.key();
+                let help = self
+                    .kernel_param_help
+                    .as_ref()
+                    .filter(|help| **help == key)
+                    .map(|_| text::caption(kernel_param_help(param)));
+                content.push(
+                    padded_control(
+                        column![
+                            row![
+                                text::body(key.clone()).width(Length::Fill),
+                                text::caption(param.value.as_str()),
+                                button::icon(icon::from_name("help-about-symbolic"))
+                                    .on_press(Message::KernelParamHelp(key)),
+                            ]
+                            .spacing(space_xxs)
+                            .align_y(Alignment::Center),
+                        ]
+                        .push_maybe(help),
+                    )
+                    .into(),
+                );
+            }
+            content.push(
+                padded_control(divider::horizontal::default())
+                    .padding([space_xxs, space_s])
+                    .into(),
+            );
+        }
+
+        if !self.history.is_empty() && !condensed {
+            content.push(
+                menu_button(text::body(fl!("export-csv")).width(Length::Fill))
+                    .on_press(Message::ExportHistory)
+                    .into(),
+            );
+            content.push(
+                menu_button(text::body(fl!("what-if")).width(Length::Fill))
+                    .on_press(Message::ToggleWhatIf)
+                    .into(),
+            );
+        }
+        if let Some(watts) = self
+            .what_if_watts
+            .filter(|_| !self.history.is_empty() && !condensed)
+        {
+            let remaining = match discharge_chart::projected_time(self.battery_energy, watts) {
+                Some(time) => fl!(
+                    "what-if-remaining",
+                    watts = format!("{watts:.0}"),
+                    time = duration_to_natural(time)
+                ),
+                None => fl!("what-if-unknown"),
+            };
+            content.push(
+                padded_control(
+                    column![
+                        discharge_chart::view(
+                            &self.history,
+                            self.battery_percent,
+                            self.battery_energy,
+                            watts
+                        ),
+                        row![
+                            slider(0.0..=WHAT_IF_MAX_WATTS, watts, Message::SetWhatIfWatts)
+                                .step(1.0),
+                            text::body(format!("{watts:.0} W")),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center),
+                        text::caption(remaining),
+                    ]
+                    .spacing(space_xxs),
+                )
+                .into(),
+            );
+        }
+
+        if condensed {
+            content.push(
+                menu_button(
+                    row![
+                        text::body(fl!("show-all-options")).width(Length::Fill),
+                        icon::from_name("go-down-symbolic").size(14).symbolic(true),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .on_press(Message::ShowAllOptions)
+                .into(),
+            );
+        }
+
+        if !condensed {
+            let actions = [
+                (
+                    self.power_actions.suspend,
+                    "system-suspend-symbolic",
+                    fl!("suspend"),
+                    Message::Suspend,
+                ),
+                (
+                    self.power_actions.hibernate,
+                    "drive-harddisk-symbolic",
+                    fl!("hibernate"),
+                    Message::Hibernate,
+                ),
+                (
+                    self.power_actions.reboot,
+                    "system-reboot-symbolic",
+                    fl!("restart"),
+                    Message::ConfirmSessionEnd(Some(SessionEnd::Reboot)),
+                ),
+                (
+                    self.power_actions.power_off,
+                    "system-shutdown-symbolic",
+                    fl!("power-off"),
+                    Message::ConfirmSessionEnd(Some(SessionEnd::PowerOff)),
+                ),
+            ];
+            for (_, icon_name, label, message) in actions.into_iter().filter(|action| action.0) {
+                content.push(
+                    menu_button(
+                        row![
+                            icon::from_name(icon_name).size(16).symbolic(true),
+                            text::body(label).width(Length::Fill),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center),
+                    )
+                    .on_press(message)
+                    .into(),
+                );
+            }
+            if let Some(action) = self.confirm_session_end {
+                let (prompt, confirm) = match action {
+                    SessionEnd::Reboot => (fl!("restart-confirm"), fl!("restart")),
+                    SessionEnd::PowerOff => (fl!("power-off-confirm"), fl!("power-off")),
+                };
+                content.push(
+                    padded_control(
+                        column![
+                            text::body(prompt),
+                            row![
+                                button::standard(fl!("cancel"))
+                                    .on_press(Message::ConfirmSessionEnd(None)),
+                                button::destructive(confirm).on_press(Message::EndSession(action)),
+                            ]
+                            .spacing(24),
+                        ]
+                        .spacing(8)
+                        .align_x(Alignment::Center),
+                    )
+                    .align_x(Alignment::Center)
+                    .into(),
+                );
+            }
+        }
+
         content.push(
             menu_button(text::body(fl!("power-settings")).width(Length::Fill))
                 .on_press(Message::OpenSettings)
                 .into(),
         );
+        if self.settings_failed {
+            content.push(padded_control(text::caption(fl!("power-settings-failed"))).into());
+        }
 
         self.core
             .applet
@@ -845,7 +4361,7 @@ impl cosmic::Application for CosmicBatteryApplet {
 
     fn subscription(&self) -> Subscription<Message> {
         let mut subscriptions = vec![
-            device_subscription(0).map(Message::UpowerDevice),
+            device_subscription(0, self.seat.clone()).map(Message::UpowerDevice),
             kbd_backlight_subscription(0).map(Message::KeyboardBacklight),
             power_profile_subscription(0).map(|event| match event {
                 PowerProfileUpdate::Update { profile } => Message::Profile(profile),
@@ -861,7 +4377,90 @@ impl cosmic::Application for CosmicBatteryApplet {
                 .as_subscription()
                 .map(|(_, now)| Message::Frame(now)),
             activation_token_subscription(0).map(Message::Token),
+            sleep_subscription(0).map(Message::Sleep),
+            screensaver_subscription(0).map(Message::ScreenSaver),
+            self.core.watch_config(Self::APP_ID).map(|u| {
+                for err in u.errors {
+                    tracing::error!(?err, "Error watching config");
+                }
+                Message::ConfigChanged(u.config)
+            }),
+            self.core
+                .watch_config::<IdleConfig>(IDLE_CONFIG_ID)
+                .map(|u| {
+                    for err in u.errors {
+                        tracing::error!(?err, "Error watching idle config");
+                    }
+                    Message::IdleConfigChanged(u.config)
+                }),
+            cpu_temperature_subscription(0).map(Message::CpuTemperature),
+            thunderbolt_subscription(0).map(Message::ThunderboltDevices),
         ];
+        if self.popup.is_some() {
+            subscriptions.push(fan_subscription(0).map(Message::Fans));
+            subscriptions.push(battery_devices_subscription(0).map(Message::BatteryDevices));
+            subscriptions.push(listen_with(|event, status, _| match (event, status) {
+                (
+                    cosmic::iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                        key: keyboard::Key::Named(key),
+                        ..
+                    }),
+                    event::Status::Ignored,
+                ) => Some(Message::PopupKey(key)),
+                _ => None,
+            }));
+            if self.low_battery_banner().is_some() {
+                subscriptions
+                    .push(interval_subscription("pulse", PULSE_REDRAW).map(|()| Message::Pulse));
+            }
+        }
+        if self.charging_limit == Some(true) {
+            subscriptions.push(tick_subscription("travel").map(|()| Message::TravelTick));
+        }
+        // past the budget, so the icon is restored at midnight
+        #[cfg(feature = "energy-goals")]
+        if self
+            .energy_goal
+            .exceeded(f64::from(self.config.daily_energy_budget))
+        {
+            subscriptions.push(tick_subscription("energy-goal").map(|()| Message::EnergyGoalTick));
+        }
+        if self.config.night_light_automatic && !self.config.night_light {
+            subscriptions.push(tick_subscription("night-light").map(|()| Message::NightLightTick));
+        }
+        if !self.config.shutdown_schedule.is_empty() || self.shutdown_at.is_some() {
+            subscriptions.push(tick_subscription(0).map(|()| Message::ScheduleTick));
+        }
+        if self.settings_daemon_sender.is_none() && self.backlight.is_some() {
+            subscriptions.push(backlight_subscription(0).map(Message::Backlight));
+        }
+        if self.tray_mode() == TrayMode::StatusNotifierItem {
+            subscriptions.push(status_notifier_subscription(0).map(Message::StatusNotifier));
+        }
+        if let Ok(panel) = std::env::var("COSMIC_PANEL_NAME") {
+            subscriptions.push(
+                cosmic_config::config_subscription::<_, CosmicPanelConfig>(
+                    "panel-config",
+                    format!("com.system76.CosmicPanel.{panel}").into(),
+                    CosmicPanelConfig::VERSION,
+                )
+                .map(|u| Message::PanelAutohide(u.config.autohide.is_some())),
+            );
+        }
+        if self.config.gsettings_sync {
+            subscriptions.push(
+                gsettings_subscription(0).map(|(key, value)| Message::GSettingsChanged(key, value)),
+            );
+        }
+        if self.config.smart_sleep && self.on_battery {
+            subscriptions
+                .push(idle_subscription(0, smart_sleep::IDLE_TIMEOUT).map(Message::InputIdle));
+            subscriptions.push(tick_subscription("smart-sleep").map(|()| Message::SmartSleepTick));
+        }
+        if self.hibernate_pending.is_some() {
+            // redraws the countdown
+            subscriptions.push(tick_subscription("hibernate").map(|()| Message::Pulse));
+        }
         if let Some(conn) = self.zbus_connection.clone() {
             subscriptions.push(settings_daemon::subscription(conn).map(Message::SettingsDaemon));
         }
@@ -876,3 +4475,60 @@ impl cosmic::Application for CosmicBatteryApplet {
         Some(cosmic::applet::style())
     }
 }
+
+fn kernel_param_help(param: &KernelParam) -> String {
+    match (param.module, param.name) {
+        ("i915", "enable_rc6") => fl!("kernel-param-rc6"),
+        ("i915", "enable_psr") => fl!("kernel-param-psr"),
+        ("i915", "enable_fbc") => fl!("kernel-param-fbc"),
+        ("i915", "enable_dc") => fl!("kernel-param-dc"),
+        (_, "runpm") => fl!("kernel-param-runpm"),
+        (_, "aspm") => fl!("kernel-param-aspm"),
+        (_, "dpm") => fl!("kernel-param-dpm"),
+        (_, "abmlevel") => fl!("kernel-param-abmlevel"),
+        _ => String::new(),
+    }
+}
+
+fn battery_device_kind(kind: DeviceKind) -> String {
+    match kind {
+        DeviceKind::Ups => fl!("device-ups"),
+        DeviceKind::Mouse => fl!("device-mouse"),
+        DeviceKind::Keyboard => fl!("device-keyboard"),
+        DeviceKind::Tablet => fl!("device-tablet"),
+        DeviceKind::Phone => fl!("device-phone"),
+        DeviceKind::GamingInput => fl!("device-gaming-input"),
+        DeviceKind::Headset => fl!("device-headset"),
+        DeviceKind::Speakers => fl!("device-speakers"),
+        DeviceKind::Headphones => fl!("device-headphones"),
+        _ => fl!("device-battery"),
+    }
+}
+
+fn battery_device_icon(kind: DeviceKind) -> &'static str {
+    match kind {
+        DeviceKind::Ups => "uninterruptible-power-supply-symbolic",
+        DeviceKind::Mouse => "input-mouse-symbolic",
+        DeviceKind::Keyboard => "input-keyboard-symbolic",
+        DeviceKind::Tablet => "input-tablet-symbolic",
+        DeviceKind::Phone => "phone-symbolic",
+        DeviceKind::GamingInput => "input-gaming-symbolic",
+        DeviceKind::Headset => "audio-headset-symbolic",
+        DeviceKind::Speakers => "audio-speakers-symbolic",
+        DeviceKind::Headphones => "audio-headphones-symbolic",
+        _ => "battery-symbolic",
+    }
+}
+
+/// Starts the Power page of cosmic-settings, reporting whether it started.
+fn spawn_settings(token: Option<String>) -> app::Task<Message> {
+    let mut cmd = std::process::Command::new("cosmic-settings");
+    cmd.arg("power");
+    if let Some(token) = token {
+        cmd.env("XDG_ACTIVATION_TOKEN", &token);
+        cmd.env("DESKTOP_STARTUP_ID", &token);
+    }
+    Task::perform(cosmic::process::spawn(cmd), |pid| {
+        cosmic::Action::App(Message::SettingsSpawned(pid.is_some()))
+    })
+}
@@ -0,0 +1,148 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The display backlight through sysfs and logind, for sessions without the
+//! COSMIC settings daemon, and whether there's a keyboard backlight at all.
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use logind_zbus::session::SessionProxy;
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    os::fd::{AsRawFd, RawFd},
+    path::Path,
+};
+use tokio::io::unix::AsyncFd;
+use zbus::Connection;
+
+const BACKLIGHT_PATH: &str = "/sys/class/backlight";
+const LEDS_PATH: &str = "/sys/class/leds";
+// the kernel suggests firmware interfaces over platform ones over raw registers
+const TYPE_PREFERENCE: [&str; 3] = ["firmware", "platform", "raw"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backlight {
+    /// Name of the device under `/sys/class/backlight`
+    pub name: String,
+    pub brightness: i32,
+    pub max: i32,
+}
+
+async fn read_attr(path: &Path) -> Option<String> {
+    let value = tokio::fs::read_to_string(path).await.ok()?;
+    Some(value.trim().to_string())
+}
+
+/// The preferred backlight device, if the display has one.
+pub async fn backlight() -> Option<Backlight> {
+    let mut entries = tokio::fs::read_dir(BACKLIGHT_PATH).await.ok()?;
+    let mut best: Option<(usize, Backlight)> = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let kind = read_attr(&path.join("type")).await.unwrap_or_default();
+        let rank = TYPE_PREFERENCE
+            .iter()
+            .position(|t| *t == kind)
+            .unwrap_or(TYPE_PREFERENCE.len());
+        if best.as_ref().is_some_and(|(best, _)| *best <= rank) {
+            continue;
+        }
+        let (Some(brightness), Some(max)) = (
+            read_attr(&path.join("brightness"))
+                .await
+                .and_then(|v| v.parse().ok()),
+            read_attr(&path.join("max_brightness"))
+                .await
+                .and_then(|v| v.parse().ok()),
+        ) else {
+            continue;
+        };
+        let Some(name) = entry.file_name().to_str().map(ToString::to_string) else {
+            continue;
+        };
+        best = Some((
+            rank,
+            Backlight {
+                name,
+                brightness,
+                max,
+            },
+        ));
+    }
+    best.map(|(_, backlight)| backlight)
+}
+
+/// Whether a keyboard backlight LED exists, which is what UPower's
+/// KbdBacklight interface controls. Named `<device>::kbd_backlight`.
+pub fn has_kbd_backlight() -> bool {
+    std::fs::read_dir(LEDS_PATH).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.ends_with("::kbd_backlight"))
+        })
+    })
+}
+
+/// Sets the brightness through logind, which lets the session owner do so
+/// without privileges, or by writing sysfs where udev rules allow it.
+pub async fn set_brightness(name: String, brightness: i32) -> zbus::Result<()> {
+    let brightness = brightness.max(0) as u32;
+    let conn = Connection::system().await?;
+    let session = SessionProxy::builder(&conn)
+        .path("/org/freedesktop/login1/session/auto")?
+        .build()
+        .await?;
+    let Err(err) = session.set_brightness("backlight", &name, brightness).await else {
+        return Ok(());
+    };
+    let path = Path::new(BACKLIGHT_PATH).join(&name).join("brightness");
+    tokio::fs::write(path, brightness.to_string())
+        .await
+        .map_err(|_| err)
+}
+
+struct MonitorSocket(udev::MonitorSocket);
+impl AsRawFd for MonitorSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+// as in the GPU monitor, the socket is only used from this task
+unsafe impl Send for MonitorSocket {}
+unsafe impl Sync for MonitorSocket {}
+
+/// Reads the backlight again whenever it changes, including from brightness
+/// keys handled by the firmware.
+pub fn backlight_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<Option<Backlight>> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let monitor = udev::MonitorBuilder::new()
+                .and_then(|builder| builder.match_subsystem("backlight"))
+                .and_then(|builder| builder.listen())
+                .and_then(|socket| AsyncFd::new(MonitorSocket(socket)));
+            match monitor {
+                Ok(monitor) => loop {
+                    let Ok(mut guard) = monitor.readable().await else {
+                        break;
+                    };
+                    for _ in monitor.get_ref().0.iter() {}
+                    guard.clear_ready();
+                    if output.send(backlight().await).await.is_err() {
+                        break;
+                    }
+                },
+                Err(err) => tracing::warn!("Failed to monitor the backlight: {err}"),
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
@@ -0,0 +1,48 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Charge thresholds through the kernel's power supply class, for hardware
+//! that system76-power doesn't manage.
+
+use std::path::Path;
+use zbus::Connection;
+
+use super::helper::BatteryHelperProxy;
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+async fn read_threshold(path: &Path) -> Option<u8> {
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The (start, end) thresholds of the first battery supporting them, or
+/// `None` if none does. Batteries without a start threshold report 0.
+pub async fn charge_thresholds() -> Option<(u8, u8)> {
+    let mut entries = tokio::fs::read_dir(POWER_SUPPLY_PATH).await.ok()?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let Some(end) = read_threshold(&path.join("charge_control_end_threshold")).await else {
+            continue;
+        };
+        let start = read_threshold(&path.join("charge_control_start_threshold"))
+            .await
+            .unwrap_or_default();
+        return Some((start, end));
+    }
+    None
+}
+
+/// Sets the thresholds of every battery through the privileged helper, which
+/// also saves them to be restored on boot.
+pub async fn set_charge_thresholds((start, end): (u8, u8)) -> zbus::Result<()> {
+    let conn = Connection::system().await?;
+    BatteryHelperProxy::new(&conn)
+        .await?
+        .set_charge_thresholds(start, end)
+        .await
+}
@@ -0,0 +1,108 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Looks for upcoming trips in the Evolution Data Server calendars.
+//!
+//! Everything here is best effort: without EDS on the session bus there are
+//! simply no trips.
+
+use chrono::{Local, NaiveDate, TimeDelta};
+use zbus::{Connection, fdo::ObjectManagerProxy, proxy, zvariant::OwnedObjectPath};
+
+const SOURCES_SERVICE: &str = "org.gnome.evolution.dataserver.Sources5";
+const SOURCES_PATH: &str = "/org/gnome/evolution/dataserver/SourceManager";
+const SOURCE_INTERFACE: &str = "org.gnome.evolution.dataserver.Source";
+
+// case-insensitive words in an event summary that suggest travelling
+const TRIP_KEYWORDS: &[&str] = &["trip", "travel", "flight", "vacation", "holiday"];
+
+#[proxy(
+    interface = "org.gnome.evolution.dataserver.CalendarFactory",
+    default_service = "org.gnome.evolution.dataserver.Calendar8",
+    default_path = "/org/gnome/evolution/dataserver/CalendarFactory"
+)]
+trait CalendarFactory {
+    /// OpenCalendar method, returning the object path and bus name
+    fn open_calendar(&self, source_uid: &str) -> zbus::Result<(OwnedObjectPath, String)>;
+}
+
+#[proxy(interface = "org.gnome.evolution.dataserver.Calendar")]
+trait Calendar {
+    /// GetObjectList method, returning iCalendar components
+    fn get_object_list(&self, query: &str) -> zbus::Result<Vec<String>>;
+}
+
+// UIDs of the calendar sources, whose key file data has a `[Calendar]` group
+async fn calendar_sources(conn: &Connection) -> zbus::Result<Vec<String>> {
+    let manager = ObjectManagerProxy::builder(conn)
+        .destination(SOURCES_SERVICE)?
+        .path(SOURCES_PATH)?
+        .build()
+        .await?;
+    let objects = manager.get_managed_objects().await?;
+    Ok(objects
+        .into_values()
+        .filter_map(|interfaces| {
+            let source = interfaces
+                .into_iter()
+                .find(|(name, _)| name.as_str() == SOURCE_INTERFACE)?
+                .1;
+            let data = String::try_from(source.get("Data")?.try_clone().ok()?).ok()?;
+            if !data.lines().any(|line| line.trim() == "[Calendar]") {
+                return None;
+            }
+            String::try_from(source.get("UID")?.try_clone().ok()?).ok()
+        })
+        .collect())
+}
+
+fn is_trip(component: &str) -> bool {
+    component
+        .lines()
+        .filter_map(|line| line.strip_prefix("SUMMARY"))
+        .filter_map(|line| line.split_once(':').map(|(_, summary)| summary))
+        .any(|summary| {
+            let summary = summary.to_lowercase();
+            TRIP_KEYWORDS
+                .iter()
+                .any(|keyword| summary.contains(keyword))
+        })
+}
+
+async fn has_trip_on(conn: &Connection, day: NaiveDate) -> zbus::Result<bool> {
+    let factory = CalendarFactoryProxy::new(conn).await?;
+    let start = day.format("%Y%m%dT000000");
+    let end = (day + TimeDelta::days(1)).format("%Y%m%dT000000");
+    let query = format!("(occur-in-time-range? (make-time \"{start}Z\") (make-time \"{end}Z\"))");
+
+    for uid in calendar_sources(conn).await? {
+        let Ok((path, bus_name)) = factory.open_calendar(&uid).await else {
+            continue;
+        };
+        let calendar = CalendarProxy::builder(conn)
+            .destination(bus_name)?
+            .path(path)?
+            .build()
+            .await?;
+        if let Ok(components) = calendar.get_object_list(&query).await {
+            if components.iter().any(|component| is_trip(component)) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Tomorrow's date if any calendar has a trip-like event then.
+pub async fn trip_tomorrow() -> Option<NaiveDate> {
+    let conn = Connection::session().await.ok()?;
+    let tomorrow = Local::now().date_naive() + TimeDelta::days(1);
+    match has_trip_on(&conn, tomorrow).await {
+        Ok(true) => Some(tomorrow),
+        Ok(false) => None,
+        Err(err) => {
+            tracing::debug!("Calendar unavailable, skipping trip check: {err}");
+            None
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::sysfs::write_privileged;
+use std::path::PathBuf;
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuGovernor {
+    pub current: String,
+    pub available: Vec<String>,
+}
+
+// every CPU's scaling_governor, cpu0 first
+async fn governor_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(CPU_PATH).await else {
+        return paths;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let Some(index) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("cpu"))
+            .and_then(|index| index.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let path = entry.path().join("cpufreq/scaling_governor");
+        if path.exists() {
+            paths.push((index, path));
+        }
+    }
+    paths.sort();
+    paths.into_iter().map(|(_, path)| path).collect()
+}
+
+pub async fn get_governor() -> Option<CpuGovernor> {
+    let cpufreq = PathBuf::from(CPU_PATH).join("cpu0/cpufreq");
+    let current = tokio::fs::read_to_string(cpufreq.join("scaling_governor"))
+        .await
+        .ok()?;
+    let available = tokio::fs::read_to_string(cpufreq.join("scaling_available_governors"))
+        .await
+        .ok()?;
+    Some(CpuGovernor {
+        current: current.trim().to_string(),
+        available: available.split_whitespace().map(String::from).collect(),
+    })
+}
+
+/// Sets the scaling governor of every CPU.
+pub async fn set_governor(governor: String) -> Result<CpuGovernor, String> {
+    for path in governor_paths().await {
+        let mut escalated = false;
+        write_privileged(&path, &governor, |helper| {
+            escalated = true;
+            let governor = governor.clone();
+            async move { helper.set_cpu_governor(&governor).await }
+        })
+        .await?;
+        // the helper sets all CPUs at once
+        if escalated {
+            break;
+        }
+    }
+    get_governor()
+        .await
+        .ok_or_else(|| "Failed to read back the CPU governor".to_string())
+}
@@ -0,0 +1,114 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Brightness of external monitors over DDC/CI, through the `ddcutil`
+//! command. Each monitor is addressed by its I2C bus, which stays the same
+//! while it's connected, unlike ddcutil's display numbers.
+
+use tokio::process::Command;
+
+// the VCP feature code of the luminance control
+const BRIGHTNESS: &str = "10";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalDisplay {
+    /// Number of the `/dev/i2c-*` device
+    pub bus: u32,
+    /// Model name the monitor reports
+    pub name: String,
+    pub brightness: u16,
+    pub max: u16,
+}
+
+async fn ddcutil(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("ddcutil")
+        .args(args)
+        .output()
+        .await
+        .map_err(|err| format!("Failed to run ddcutil: {err}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Monitors that answered the detection, by bus and model name.
+fn parse_detect(output: &str) -> Vec<(u32, String)> {
+    let mut displays = Vec::new();
+    // Display <n>, then indented fields, or Invalid display for monitors
+    // without DDC/CI
+    for block in output.split("\n\n") {
+        if !block.trim_start().starts_with("Display ") {
+            continue;
+        }
+        let mut bus = None;
+        let mut name = None;
+        for line in block.lines().map(str::trim) {
+            if let Some(path) = line.strip_prefix("I2C bus:") {
+                bus = path
+                    .trim()
+                    .strip_prefix("/dev/i2c-")
+                    .and_then(|n| n.parse().ok());
+            } else if let Some(monitor) = line.strip_prefix("Monitor:") {
+                // <manufacturer>:<model>:<serial>
+                name = monitor.trim().split(':').nth(1).map(str::to_string);
+            }
+        }
+        if let Some(bus) = bus {
+            displays.push((bus, name.unwrap_or_else(|| format!("i2c-{bus}"))));
+        }
+    }
+    displays
+}
+
+/// The current and maximum brightness of the monitor on `bus`.
+async fn brightness(bus: u32) -> Result<(u16, u16), String> {
+    let output = ddcutil(&["--bus", &bus.to_string(), "--brief", "getvcp", BRIGHTNESS]).await?;
+    // VCP 10 C <current> <max>
+    let mut values = output.split_whitespace().skip(3).map(str::parse::<u16>);
+    match (values.next(), values.next()) {
+        (Some(Ok(current)), Some(Ok(max))) if max > 0 => Ok((current, max)),
+        _ => Err(format!(
+            "Unexpected brightness from i2c-{bus}: {}",
+            output.trim()
+        )),
+    }
+}
+
+/// External monitors whose brightness can be set, empty without ddcutil.
+/// Detection takes a second or so per monitor.
+pub async fn external_displays() -> Vec<ExternalDisplay> {
+    let detected = match ddcutil(&["detect", "--brief"]).await {
+        Ok(output) => parse_detect(&output),
+        Err(err) => {
+            tracing::debug!("DDC/CI is unavailable: {err}");
+            return Vec::new();
+        }
+    };
+    let mut displays = Vec::new();
+    for (bus, name) in detected {
+        match brightness(bus).await {
+            Ok((brightness, max)) => displays.push(ExternalDisplay {
+                bus,
+                name,
+                brightness,
+                max,
+            }),
+            Err(err) => tracing::debug!("Skipping {name}: {err}"),
+        }
+    }
+    displays
+}
+
+pub async fn set_brightness(bus: u32, brightness: u16) -> Result<(), String> {
+    tracing::debug!("Setting the brightness of the display on bus {bus} to {brightness}");
+    ddcutil(&[
+        "--bus",
+        &bus.to_string(),
+        "setvcp",
+        BRIGHTNESS,
+        &brightness.to_string(),
+    ])
+    .await
+    .map(|_| ())
+}
@@ -0,0 +1,525 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use futures::StreamExt;
+use std::{fmt::Debug, hash::Hash, path::Path, time::Duration};
+use zbus::{
+    Connection,
+    fdo::{PropertiesChanged, PropertiesProxy},
+    names::InterfaceName,
+    proxy::CacheProperties,
+    zvariant::OwnedObjectPath,
+};
+
+use super::{
+    logind::{PRIMARY_SEAT, seats},
+    upower::UPowerProxy,
+    upower_device::{BatteryState, DeviceKind, DeviceProxy, WarningLevel},
+};
+
+/// Snapshot of the UPower display device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisplayDevice {
+    pub on_battery: bool,
+    pub percent: f64,
+    pub state: BatteryState,
+    /// Watt-hours currently stored
+    pub energy: f64,
+    /// Watts, always positive
+    pub energy_rate: f64,
+    /// Seconds, 0 when unknown
+    pub time_to_empty: i64,
+    /// Seconds, 0 when unknown
+    pub time_to_full: i64,
+    pub warning_level: WarningLevel,
+}
+
+impl DisplayDevice {
+    async fn read(upower: &UPowerProxy<'_>, device: &DeviceProxy<'_>) -> zbus::Result<Self> {
+        Ok(Self {
+            on_battery: upower.on_battery().await?,
+            percent: device.percentage().await?,
+            state: device.state().await?.into(),
+            energy: device.energy().await.unwrap_or_default(),
+            energy_rate: device.energy_rate().await.unwrap_or_default().abs(),
+            time_to_empty: device.time_to_empty().await.unwrap_or_default(),
+            time_to_full: device.time_to_full().await.unwrap_or_default(),
+            warning_level: device
+                .warning_level()
+                .await
+                .map(WarningLevel::from)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Average energy rate over `period`, sampled every second, or `None` if
+/// the battery wasn't discharging throughout.
+pub async fn sample_energy_rate(period: Duration) -> zbus::Result<Option<f64>> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    let device = DeviceProxy::builder(&conn)
+        .path(upower.get_display_device().await?)?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+    let mut samples = Vec::new();
+    let start = tokio::time::Instant::now();
+    while start.elapsed() < period {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if !upower.on_battery().await? {
+            return Ok(None);
+        }
+        samples.push(device.energy_rate().await?.abs());
+    }
+    if samples.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(samples.iter().sum::<f64>() / samples.len() as f64))
+}
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+const UDEV_DATA_PATH: &str = "/run/udev/data";
+const BATTERY_DEVICES_REFRESH: Duration = Duration::from_secs(60);
+// wait before reaching UPower again, doubling after each failed attempt
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+// UPower often spreads one change, like plugging in, over several signals
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Wear and temperature of a single battery, where UPower reports them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatteryDetails {
+    /// Full capacity as a percentage of the design capacity
+    pub capacity: Option<f64>,
+    /// Watt-hours when fully charged
+    pub energy_full: Option<f64>,
+    pub charge_cycles: Option<u32>,
+    /// Degrees Celsius
+    pub temperature: Option<f64>,
+    /// Embedded controller firmware version, from sysfs
+    pub firmware_version: Option<String>,
+    pub model: String,
+    /// `org.freedesktop.UPower.Device.Technology`
+    pub technology: u32,
+    /// Volts
+    pub voltage: Option<f64>,
+    /// Volts, from sysfs
+    pub voltage_min_design: Option<f64>,
+}
+
+// how far below the minimum design voltage counts as low
+const LOW_VOLTAGE_MARGIN: f64 = 0.5;
+
+impl BatteryDetails {
+    /// The pack voltage is well below the design minimum, which may point to a
+    /// failing cell.
+    pub fn low_voltage(&self) -> bool {
+        self.voltage
+            .zip(self.voltage_min_design)
+            .is_some_and(|(voltage, min)| voltage < min - LOW_VOLTAGE_MARGIN)
+    }
+}
+
+async fn read_sysfs(path: impl AsRef<Path>) -> Option<String> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    let contents = contents.trim();
+    (!contents.is_empty()).then(|| contents.to_string())
+}
+
+/// Reads the details of the first system battery.
+pub async fn battery_details() -> zbus::Result<Option<BatteryDetails>> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    for path in upower.enumerate_devices().await? {
+        let device = DeviceProxy::builder(&conn).path(path)?.build().await?;
+        if DeviceKind::from(device.type_().await?) != DeviceKind::Battery
+            || !device.power_supply().await.unwrap_or_default()
+        {
+            continue;
+        }
+
+        let (firmware_version, voltage_min_design) = match device.native_path().await {
+            Ok(name) if !name.is_empty() => {
+                let path = Path::new(POWER_SUPPLY_PATH).join(name);
+                (
+                    read_sysfs(path.join("firmware_version")).await,
+                    read_sysfs(path.join("voltage_min_design"))
+                        .await
+                        .and_then(|microvolts| microvolts.parse::<f64>().ok())
+                        .map(|microvolts| microvolts / 1_000_000.0),
+                )
+            }
+            _ => (None, None),
+        };
+        let full = device.energy_full().await.unwrap_or_default();
+        let design = device.energy_full_design().await.unwrap_or_default();
+        // UPower uses 0 or -1 for values the hardware doesn't report
+        return Ok(Some(BatteryDetails {
+            capacity: (full > 0.0 && design > 0.0).then(|| full / design * 100.0),
+            energy_full: (full > 0.0).then_some(full),
+            charge_cycles: device
+                .charge_cycles()
+                .await
+                .ok()
+                .and_then(|cycles| u32::try_from(cycles).ok()),
+            temperature: device.temperature().await.ok().filter(|t| *t != 0.0),
+            firmware_version,
+            model: device.model().await.unwrap_or_default(),
+            technology: device.technology().await.unwrap_or_default(),
+            voltage: device.voltage().await.ok().filter(|v| *v > 0.0),
+            voltage_min_design: voltage_min_design.filter(|v| *v > 0.0),
+        }));
+    }
+    Ok(None)
+}
+
+/// A charge reading UPower recorded, with the power draw at the time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCharge {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub percent: f64,
+    /// Watts, always positive
+    pub watts: f64,
+    pub state: BatteryState,
+}
+
+/// Charge readings of the first system battery over the last `timespan`,
+/// oldest first, at most `points` of them. UPower keeps these across
+/// restarts, unlike the applet's own history.
+pub async fn charge_history(timespan: Duration, points: u32) -> zbus::Result<Vec<RecordedCharge>> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    let timespan = timespan.as_secs() as u32;
+    for path in upower.enumerate_devices().await? {
+        let device = DeviceProxy::builder(&conn).path(path)?.build().await?;
+        if DeviceKind::from(device.type_().await?) != DeviceKind::Battery
+            || !device.power_supply().await.unwrap_or_default()
+        {
+            continue;
+        }
+
+        // (unix time, value, state), newest first
+        let mut charge = device.get_history("charge", timespan, points).await?;
+        let rate = device
+            .get_history("rate", timespan, points)
+            .await
+            .unwrap_or_default();
+        charge.sort_by_key(|(time, _, _)| *time);
+        return Ok(charge
+            .into_iter()
+            .filter_map(|(time, percent, state)| {
+                // the rate recorded last by then
+                let watts = rate
+                    .iter()
+                    .filter(|(rate_time, _, _)| *rate_time <= time)
+                    .max_by_key(|(rate_time, _, _)| *rate_time)
+                    .map_or(0.0, |(_, watts, _)| watts.abs());
+                let time = chrono::DateTime::from_timestamp(i64::from(time), 0)?;
+                Some(RecordedCharge {
+                    time: time.with_timezone(&chrono::Local),
+                    percent,
+                    watts,
+                    state: state.into(),
+                })
+            })
+            .collect());
+    }
+    Ok(Vec::new())
+}
+
+/// A UPower device with all of its raw properties, as `upower --dump` shows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerDevice {
+    pub path: String,
+    pub kind: DeviceKind,
+    pub state: BatteryState,
+    pub percent: f64,
+    /// Property names and values, sorted by name
+    pub properties: Vec<(String, String)>,
+}
+
+pub async fn power_devices() -> zbus::Result<Vec<PowerDevice>> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    let interface = InterfaceName::from_static_str_unchecked("org.freedesktop.UPower.Device");
+    let mut devices = Vec::new();
+    for path in upower.enumerate_devices().await? {
+        let properties = PropertiesProxy::builder(&conn)
+            .destination("org.freedesktop.UPower")?
+            .path(path.clone())?
+            .build()
+            .await?
+            .get_all(interface.clone())
+            .await?;
+        let kind = properties
+            .get("Type")
+            .and_then(|value| u32::try_from(value).ok())
+            .unwrap_or_default();
+        let state = properties
+            .get("State")
+            .and_then(|value| u32::try_from(value).ok())
+            .unwrap_or_default();
+        let percent = properties
+            .get("Percentage")
+            .and_then(|value| f64::try_from(value).ok())
+            .unwrap_or_default();
+        let mut properties: Vec<(String, String)> = properties
+            .into_iter()
+            .map(|(name, value)| (name, value.to_string()))
+            .collect();
+        properties.sort();
+        devices.push(PowerDevice {
+            path: path.to_string(),
+            kind: kind.into(),
+            state: state.into(),
+            percent,
+            properties,
+        });
+    }
+    Ok(devices)
+}
+
+/// A battery powered device UPower knows about, such as a second battery,
+/// a wireless mouse or a UPS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatteryDevice {
+    pub path: String,
+    pub kind: DeviceKind,
+    /// Vendor and model, where reported
+    pub name: String,
+    pub percent: f64,
+    pub state: BatteryState,
+}
+
+async fn battery_devices(
+    conn: &Connection,
+    upower: &UPowerProxy<'_>,
+) -> zbus::Result<Vec<BatteryDevice>> {
+    let mut devices = Vec::new();
+    for path in upower.enumerate_devices().await? {
+        let device = DeviceProxy::builder(conn)
+            .path(path.clone())?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        let kind = DeviceKind::from(device.type_().await?);
+        let battery_powered = matches!(
+            kind,
+            DeviceKind::Battery
+                | DeviceKind::Ups
+                | DeviceKind::Mouse
+                | DeviceKind::Keyboard
+                | DeviceKind::Tablet
+                | DeviceKind::Phone
+                | DeviceKind::GamingInput
+                | DeviceKind::Headset
+                | DeviceKind::Speakers
+                | DeviceKind::Headphones
+        );
+        if !battery_powered || !device.is_present().await.unwrap_or_default() {
+            continue;
+        }
+        let vendor = device.vendor().await.unwrap_or_default();
+        let model = device.model().await.unwrap_or_default();
+        devices.push(BatteryDevice {
+            path: path.to_string(),
+            kind,
+            name: format!("{vendor} {model}").trim().to_string(),
+            percent: device.percentage().await.unwrap_or_default(),
+            state: device.state().await.unwrap_or_default().into(),
+        });
+    }
+    Ok(devices)
+}
+
+/// Lists the battery powered devices again whenever one is added or
+/// removed, and every minute for their charge.
+pub fn battery_devices_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<Vec<BatteryDevice>> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            if let Err(err) = watch_battery_devices(&mut output).await {
+                tracing::error!("UPower device watch failed: {err}");
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+async fn watch_battery_devices(
+    output: &mut futures::channel::mpsc::Sender<Vec<BatteryDevice>>,
+) -> zbus::Result<()> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    let mut added = upower.receive_device_added().await?;
+    let mut removed = upower.receive_device_removed().await?;
+
+    loop {
+        let devices = battery_devices(&conn, &upower).await?;
+        if output.send(devices).await.is_err() {
+            return Ok(());
+        }
+
+        tokio::select! {
+            Some(_) = added.next() => {}
+            Some(_) = removed.next() => {}
+            _ = tokio::time::sleep(BATTERY_DEVICES_REFRESH) => {}
+        }
+    }
+}
+
+/// Finds a battery udev assigned to `seat`. Untagged devices belong to the
+/// primary seat, which uses the display device instead.
+async fn seat_battery(
+    conn: &Connection,
+    upower: &UPowerProxy<'_>,
+    seat: &str,
+) -> zbus::Result<Option<OwnedObjectPath>> {
+    let tag = format!("E:ID_SEAT={seat}");
+    for path in upower.enumerate_devices().await? {
+        let device = DeviceProxy::builder(conn)
+            .path(path.clone())?
+            .build()
+            .await?;
+        if DeviceKind::from(device.type_().await?) != DeviceKind::Battery {
+            continue;
+        }
+        let Ok(name) = device.native_path().await else {
+            continue;
+        };
+        let udev_data = Path::new(UDEV_DATA_PATH).join(format!("+power_supply:{name}"));
+        let Ok(contents) = tokio::fs::read_to_string(udev_data).await else {
+            continue;
+        };
+        if contents.lines().any(|line| line == tag) {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Debug, Clone)]
+pub enum DeviceDbusEvent {
+    Update(DisplayDevice),
+    /// There's no battery, or it was removed
+    NoBattery,
+    /// UPower failed again on retrying, with the last error
+    Unreachable(String),
+}
+
+/// Watches the display device, or on a secondary seat the battery assigned
+/// to that seat if there is one. If UPower goes away, for instance when
+/// upowerd restarts, the watch starts over once it's back.
+pub fn device_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+    seat: String,
+) -> iced::Subscription<DeviceDbusEvent> {
+    Subscription::run_with_id(
+        (id, seat.clone()),
+        stream::channel(50, move |mut output| async move {
+            let mut delay = RECONNECT_DELAY;
+            while let Err(err) = watch_display_device(&mut output, &seat, &mut delay).await {
+                tracing::warn!("UPower display device watch failed, retrying in {delay:?}: {err}");
+                // a single failure is usually upowerd restarting
+                if delay > RECONNECT_DELAY {
+                    let _ = output
+                        .send(DeviceDbusEvent::Unreachable(err.to_string()))
+                        .await;
+                }
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+/// Ends with an error when UPower goes away, and `Ok` once the events aren't
+/// received anymore. `delay` is reset once the device has been read.
+async fn watch_display_device(
+    output: &mut futures::channel::mpsc::Sender<DeviceDbusEvent>,
+    seat: &str,
+    delay: &mut Duration,
+) -> zbus::Result<()> {
+    let conn = Connection::system().await?;
+    let upower = UPowerProxy::new(&conn).await?;
+    let multi_seat = seat != PRIMARY_SEAT && seats().await.is_ok_and(|seats| seats.len() > 1);
+    let seat_path = if multi_seat {
+        seat_battery(&conn, &upower, seat).await?
+    } else {
+        None
+    };
+    let path = match seat_path {
+        Some(path) => path,
+        None => upower.get_display_device().await?,
+    };
+    // Properties are re-read after every change signal, so skip the cache
+    let device = DeviceProxy::builder(&conn)
+        .path(path.clone())?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+
+    let properties = PropertiesProxy::builder(&conn)
+        .destination("org.freedesktop.UPower")?
+        .path(path)?
+        .build()
+        .await?;
+    let mut device_changed = properties.receive_properties_changed().await?;
+    let mut on_battery_changed = upower.receive_on_battery_changed().await;
+    let mut owner_changed = upower.inner().receive_owner_changed().await?;
+
+    loop {
+        // the display device stays when batteries are removed, with another type
+        let present = DeviceKind::from(device.type_().await?) == DeviceKind::Battery
+            && device.is_present().await.unwrap_or(true);
+        let event = if present {
+            DeviceDbusEvent::Update(DisplayDevice::read(&upower, &device).await?)
+        } else {
+            DeviceDbusEvent::NoBattery
+        };
+        *delay = RECONNECT_DELAY;
+        if output.send(event).await.is_err() {
+            return Ok(());
+        }
+
+        tokio::select! {
+            Some(signal) = device_changed.next() => {
+                log_changes(&signal);
+                // gather the rest of the burst, so steady signals still get
+                // the device read every CHANGE_DEBOUNCE
+                let deadline = tokio::time::sleep(CHANGE_DEBOUNCE);
+                tokio::pin!(deadline);
+                loop {
+                    tokio::select! {
+                        () = &mut deadline => break,
+                        Some(signal) = device_changed.next() => log_changes(&signal),
+                        else => break,
+                    }
+                }
+            }
+            Some(_) = on_battery_changed.next() => {}
+            Some(None) = owner_changed.next() => {
+                return Err(zbus::Error::Failure("UPower left the bus".to_string()));
+            }
+            else => return Err(zbus::Error::Failure("UPower signals ended".to_string())),
+        }
+    }
+}
+
+fn log_changes(signal: &PropertiesChanged) {
+    if let Ok(args) = signal.args() {
+        for (name, value) in args.changed_properties() {
+            tracing::debug!("UPower {name} changed to {value:?}");
+        }
+    }
+}
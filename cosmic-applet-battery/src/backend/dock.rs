@@ -0,0 +1,129 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    os::fd::{AsRawFd, RawFd},
+    path::Path,
+};
+use tokio::io::unix::AsyncFd;
+
+const THUNDERBOLT_PATH: &str = "/sys/bus/thunderbolt/devices";
+const TYPEC_PATH: &str = "/sys/class/typec";
+
+/// A connected Thunderbolt device, such as a dock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThunderboltDevice {
+    /// `unique_id`, the same on every connection
+    pub uuid: String,
+    pub name: String,
+}
+
+async fn read_attr(path: &Path) -> Option<String> {
+    let value = tokio::fs::read_to_string(path).await.ok()?;
+    Some(value.trim().to_string())
+}
+
+// Thunderbolt devices other than the host controller itself (`0-0`) are
+// named `<domain>-<route>`, e.g. `0-1`.
+pub async fn thunderbolt_devices() -> Vec<ThunderboltDevice> {
+    let mut devices = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(THUNDERBOLT_PATH).await else {
+        return devices;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let Some((domain, route)) = name.split_once('-') else {
+            continue;
+        };
+        if domain.parse::<u32>().is_err()
+            || route == "0"
+            || !route.chars().all(|c| c.is_ascii_digit())
+        {
+            continue;
+        }
+        let path = entry.path();
+        let Some(device_name) = read_attr(&path.join("device_name")).await else {
+            continue;
+        };
+        let vendor = read_attr(&path.join("vendor_name"))
+            .await
+            .unwrap_or_default();
+        devices.push(ThunderboltDevice {
+            uuid: read_attr(&path.join("unique_id")).await.unwrap_or_default(),
+            name: format!("{vendor} {device_name}").trim().to_string(),
+        });
+    }
+    devices
+}
+
+// A connected USB-C partner shows up as `portN-partner`
+async fn typec_partner_present() -> bool {
+    let Ok(mut entries) = tokio::fs::read_dir(TYPEC_PATH).await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.ends_with("-partner"))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+pub async fn is_docked() -> bool {
+    !thunderbolt_devices().await.is_empty() || typec_partner_present().await
+}
+
+struct MonitorSocket(udev::MonitorSocket);
+impl AsRawFd for MonitorSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+// as in the GPU monitor, the socket is only used from this task
+unsafe impl Send for MonitorSocket {}
+unsafe impl Sync for MonitorSocket {}
+
+/// Lists the connected Thunderbolt devices, and again whenever one is added
+/// or removed.
+pub fn thunderbolt_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<Vec<ThunderboltDevice>> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let monitor = udev::MonitorBuilder::new()
+                .and_then(|builder| builder.match_subsystem("thunderbolt"))
+                .and_then(|builder| builder.listen())
+                .and_then(|socket| AsyncFd::new(MonitorSocket(socket)));
+            match monitor {
+                Ok(monitor) => loop {
+                    if output.send(thunderbolt_devices().await).await.is_err() {
+                        break;
+                    }
+                    let Ok(mut guard) = monitor.readable().await else {
+                        break;
+                    };
+                    // which device changed doesn't matter, the list is read again
+                    for _ in monitor.get_ref().0.iter() {}
+                    guard.clear_ready();
+                },
+                Err(err) => tracing::warn!("Failed to monitor Thunderbolt devices: {err}"),
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
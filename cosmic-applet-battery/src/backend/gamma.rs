@@ -0,0 +1,280 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Night light through the `wlr-gamma-control-unstable-v1` Wayland protocol.
+//!
+//! The compositor keeps the gamma ramps set by a client only while that
+//! client holds the output's gamma control, and restores the original ramps
+//! once it's destroyed. Outputs are bound when the controls are first taken,
+//! so a display plugged in later stays untinted until night light is turned
+//! off and on again.
+
+use cctk::sctk::reexports::{
+    client::{
+        self as wayland_client, Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+        backend::ObjectId,
+        globals::{GlobalList, GlobalListContents, registry_queue_init},
+        protocol::{wl_output::WlOutput, wl_registry::WlRegistry},
+    },
+    protocols_wlr::gamma_control::v1::client::{
+        zwlr_gamma_control_manager_v1::ZwlrGammaControlManagerV1,
+        zwlr_gamma_control_v1::{self, ZwlrGammaControlV1},
+    },
+};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{Seek, Write},
+    os::fd::AsFd,
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender, channel},
+};
+
+/// Coolest temperature offered, in kelvin.
+pub const MIN_TEMPERATURE: u32 = 2500;
+/// Temperature of an untinted screen, in kelvin.
+pub const MAX_TEMPERATURE: u32 = 6500;
+
+#[derive(Default)]
+struct State {
+    /// Entries in each ramp, by gamma control
+    sizes: HashMap<ObjectId, u32>,
+    /// Controls the compositor has taken back, still to be destroyed
+    failed: Vec<ObjectId>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlOutput, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlOutput,
+        _: <WlOutput as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrGammaControlManagerV1, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrGammaControlManagerV1,
+        _: <ZwlrGammaControlManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrGammaControlV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        control: &ZwlrGammaControlV1,
+        event: zwlr_gamma_control_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_gamma_control_v1::Event::GammaSize { size } => {
+                state.sizes.insert(control.id(), size);
+            }
+            zwlr_gamma_control_v1::Event::Failed => {
+                // another client holds the output, or it went away
+                tracing::warn!("Lost the gamma control of an output");
+                state.sizes.remove(&control.id());
+                state.failed.push(control.id());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Handle to the thread owning the gamma controls.
+#[derive(Debug, Clone)]
+pub struct GammaControl(Sender<Option<u32>>);
+
+impl GammaControl {
+    /// Connects to the compositor on a thread of its own.
+    pub fn spawn() -> Self {
+        let (tx, rx) = channel();
+        std::thread::spawn(move || {
+            if let Err(err) = run(rx) {
+                tracing::warn!("Night light is unavailable: {err}");
+            }
+        });
+        Self(tx)
+    }
+
+    /// Tints every output to `kelvin`, or restores their gamma with `None`.
+    pub fn set(&self, kelvin: Option<u32>) {
+        let _ = self.0.send(kelvin);
+    }
+}
+
+/// Blocks, applying each request until the sender is dropped.
+fn run(rx: Receiver<Option<u32>>) -> Result<(), String> {
+    let conn = Connection::connect_to_env().map_err(|err| err.to_string())?;
+    let (globals, mut queue) =
+        registry_queue_init::<State>(&conn).map_err(|err| err.to_string())?;
+    let qh = queue.handle();
+    let manager: ZwlrGammaControlManagerV1 = globals
+        .bind(&qh, 1..=1, ())
+        .map_err(|err| err.to_string())?;
+
+    let mut state = State::default();
+    let mut outputs = Vec::new();
+    // by output
+    let mut controls = HashMap::new();
+    for kelvin in rx {
+        let Some(kelvin) = kelvin else {
+            for (_, control) in controls.drain() {
+                control.destroy();
+            }
+            outputs.clear();
+            state.sizes.clear();
+            state.failed.clear();
+            queue.flush().map_err(|err| err.to_string())?;
+            continue;
+        };
+
+        queue
+            .dispatch_pending(&mut state)
+            .map_err(|err| err.to_string())?;
+        prune(&mut controls, &mut state);
+        if outputs.is_empty() {
+            outputs = bind_outputs(&globals, &qh);
+        }
+        // outputs without a control yet, or whose control failed
+        let mut taken = false;
+        for output in &outputs {
+            controls.entry(output.id()).or_insert_with(|| {
+                taken = true;
+                manager.get_gamma_control(output, &qh, ())
+            });
+        }
+        if taken {
+            // for the ramp sizes
+            queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+        }
+        apply(controls.values(), &state, &mut queue, kelvin)?;
+    }
+    Ok(())
+}
+
+fn bind_outputs(globals: &GlobalList, qh: &QueueHandle<State>) -> Vec<WlOutput> {
+    globals.contents().with_list(|list| {
+        list.iter()
+            .filter(|global| global.interface == WlOutput::interface().name)
+            .map(|global| {
+                globals
+                    .registry()
+                    .bind(global.name, global.version.min(4), qh, ())
+            })
+            .collect()
+    })
+}
+
+/// Destroys the controls that failed, which can't be used anymore.
+fn prune(controls: &mut HashMap<ObjectId, ZwlrGammaControlV1>, state: &mut State) {
+    controls.retain(|_, control| {
+        let failed = state.failed.contains(&control.id());
+        if failed {
+            control.destroy();
+        }
+        !failed
+    });
+    state.failed.clear();
+}
+
+fn apply<'a>(
+    controls: impl Iterator<Item = &'a ZwlrGammaControlV1>,
+    state: &State,
+    queue: &mut EventQueue<State>,
+    kelvin: u32,
+) -> Result<(), String> {
+    for control in controls {
+        let Some(&size) = state.sizes.get(&control.id()) else {
+            continue;
+        };
+        let file = ramp_file(&ramps(size, kelvin))
+            .map_err(|err| format!("Failed to write the gamma ramps: {err}"))?;
+        control.set_gamma(file.as_fd());
+    }
+    queue.flush().map_err(|err| err.to_string())
+}
+
+/// Red, green and blue ramps of `size` entries each, in native byte order.
+fn ramps(size: u32, kelvin: u32) -> Vec<u8> {
+    let last = f64::from(size.max(2) - 1);
+    let (red, green, blue) = white_point(kelvin);
+    let mut bytes = Vec::with_capacity(size as usize * 6);
+    for factor in [red, green, blue] {
+        for i in 0..size {
+            let value = f64::from(i) / last * factor * f64::from(u16::MAX);
+            bytes.extend_from_slice(&(value.round() as u16).to_ne_bytes());
+        }
+    }
+    bytes
+}
+
+/// The colour of a black body at `kelvin`, as RGB factors from 0 to 1.
+///
+/// Tanner Helland's fit of the CIE 1964 colour matching functions, close
+/// enough for tinting between 1000 K and 40000 K.
+fn white_point(kelvin: u32) -> (f64, f64, f64) {
+    let t = f64::from(kelvin.clamp(1000, 40000)) / 100.0;
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_727_446 * (t - 60.0).powf(-0.133_204_759_2)
+    };
+    let green = if t <= 66.0 {
+        99.470_802_586_1 * t.ln() - 161.119_568_166_1
+    } else {
+        288.122_169_528_3 * (t - 60.0).powf(-0.075_514_849_2)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7
+    };
+    let factor = |c: f64| (c / 255.0).clamp(0.0, 1.0);
+    (factor(red), factor(green), factor(blue))
+}
+
+/// An anonymous file holding `bytes`, read from the start by the compositor.
+fn ramp_file(bytes: &[u8]) -> std::io::Result<File> {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let path = dir.join(format!(
+        "cosmic-applet-battery-gamma-{}",
+        std::process::id()
+    ));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)?;
+    std::fs::remove_file(&path)?;
+    file.write_all(bytes)?;
+    file.rewind()?;
+    Ok(file)
+}
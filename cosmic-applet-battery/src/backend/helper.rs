@@ -0,0 +1,34 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! # DBus interface proxy for: `com.system76.CosmicAppletBatteryHelper`
+//!
+//! The privileged helper shipped as `cosmic-applet-battery-helper`, which
+//! performs sysfs writes after checking the caller against polkit.
+
+use zbus::proxy;
+
+#[proxy(
+    interface = "com.system76.CosmicAppletBatteryHelper",
+    default_service = "com.system76.CosmicAppletBatteryHelper",
+    default_path = "/com/system76/CosmicAppletBatteryHelper"
+)]
+pub trait BatteryHelper {
+    /// SetChargeThresholds method
+    fn set_charge_thresholds(&self, start: u8, end: u8) -> zbus::Result<()>;
+
+    /// SetCpuGovernor method
+    fn set_cpu_governor(&self, governor: &str) -> zbus::Result<()>;
+
+    /// SetCpuTdp method
+    fn set_cpu_tdp(&self, microwatts: u64) -> zbus::Result<()>;
+
+    /// SetUsbPowerControl method
+    fn set_usb_power_control(&self, device: &str, control: &str) -> zbus::Result<()>;
+
+    /// SetWakeOnLan method
+    fn set_wake_on_lan(&self, interface: &str, enabled: bool) -> zbus::Result<()>;
+
+    /// SetMemSleep method
+    fn set_mem_sleep(&self, state: &str) -> zbus::Result<()>;
+}
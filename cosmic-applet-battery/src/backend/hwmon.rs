@@ -0,0 +1,137 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use std::{fmt::Debug, hash::Hash, path::Path, time::Duration};
+
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fan {
+    /// Driver-provided label, e.g. `CPU Fan`
+    pub label: Option<String>,
+    pub rpm: u32,
+}
+
+async fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    let contents = contents.trim();
+    (!contents.is_empty()).then(|| contents.to_string())
+}
+
+pub async fn read_fans() -> Vec<Fan> {
+    let mut fans = Vec::new();
+    let Ok(mut hwmons) = tokio::fs::read_dir(HWMON_PATH).await else {
+        return fans;
+    };
+    while let Ok(Some(hwmon)) = hwmons.next_entry().await {
+        let hwmon = hwmon.path();
+        let Ok(mut attributes) = tokio::fs::read_dir(&hwmon).await else {
+            continue;
+        };
+        let mut inputs = Vec::new();
+        while let Ok(Some(attribute)) = attributes.next_entry().await {
+            let name = attribute.file_name();
+            let Some(index) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("fan"))
+                .and_then(|name| name.strip_suffix("_input"))
+                .and_then(|index| index.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            inputs.push(index);
+        }
+        inputs.sort_unstable();
+
+        for index in inputs {
+            let Some(rpm) = read_trimmed(hwmon.join(format!("fan{index}_input")))
+                .await
+                .and_then(|rpm| rpm.parse().ok())
+            else {
+                continue;
+            };
+            fans.push(Fan {
+                label: read_trimmed(hwmon.join(format!("fan{index}_label"))).await,
+                rpm,
+            });
+        }
+    }
+    fans
+}
+
+// drivers reporting the CPU package temperature as temp1
+const CPU_SENSORS: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+
+/// CPU package temperature in °C.
+pub async fn read_cpu_temperature() -> Option<f64> {
+    let mut hwmons = tokio::fs::read_dir(HWMON_PATH).await.ok()?;
+    while let Ok(Some(hwmon)) = hwmons.next_entry().await {
+        let hwmon = hwmon.path();
+        let Some(name) = read_trimmed(hwmon.join("name")).await else {
+            continue;
+        };
+        if !CPU_SENSORS.contains(&name.as_str()) {
+            continue;
+        }
+        let millidegrees: f64 = read_trimmed(hwmon.join("temp1_input"))
+            .await?
+            .parse()
+            .ok()?;
+        return Some(millidegrees / 1000.0);
+    }
+    None
+}
+
+pub fn cpu_temperature_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<f64> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                let Some(temperature) = read_cpu_temperature().await else {
+                    continue;
+                };
+                if output.send(temperature).await.is_err() {
+                    break;
+                }
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+pub fn fan_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<Vec<Fan>> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last = None;
+
+            loop {
+                interval.tick().await;
+                let fans = read_fans().await;
+                if last.as_ref() != Some(&fans) {
+                    last = Some(fans.clone());
+                    if output.send(fans).await.is_err() {
+                        break;
+                    }
+                }
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
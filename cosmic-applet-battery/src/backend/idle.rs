@@ -0,0 +1,79 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Idle timeouts shared with cosmic-idle and the COSMIC Settings power page.
+
+use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+
+pub const IDLE_CONFIG_ID: &str = "com.system76.CosmicIdle";
+
+/// Mirror of the cosmic-idle config schema. Times are in milliseconds, with
+/// `None` disabling the timeout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, CosmicConfigEntry, PartialEq, Eq)]
+#[version = 1]
+pub struct IdleConfig {
+    pub screen_off_time: Option<u32>,
+    pub suspend_on_ac_time: Option<u32>,
+    pub suspend_on_battery_time: Option<u32>,
+}
+
+/// Screen timeouts offered in the applet, in minutes, with 0 meaning never.
+pub const SCREEN_TIMEOUT_PRESETS: [u32; 6] = [1, 2, 5, 10, 30, 0];
+
+/// Delays in minutes, with 0 meaning never.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SuspendDelays {
+    pub ac: u32,
+    pub battery: u32,
+}
+
+fn to_minutes(ms: Option<u32>) -> u32 {
+    ms.map_or(0, |ms| ms / 60_000)
+}
+
+fn to_ms(minutes: u32) -> Option<u32> {
+    (minutes > 0).then(|| minutes.saturating_mul(60_000))
+}
+
+impl IdleConfig {
+    pub fn suspend_delays(&self) -> SuspendDelays {
+        SuspendDelays {
+            ac: to_minutes(self.suspend_on_ac_time),
+            battery: to_minutes(self.suspend_on_battery_time),
+        }
+    }
+
+    /// Minutes until the screen turns off, 0 for never.
+    pub fn screen_off_minutes(&self) -> u32 {
+        to_minutes(self.screen_off_time)
+    }
+
+    pub fn set_screen_off_minutes(&mut self, config: &cosmic_config::Config, minutes: u32) {
+        if let Err(err) = self.set_screen_off_time(config, to_ms(minutes)) {
+            tracing::error!(?err, "Failed to set screen_off_time");
+        }
+    }
+
+    pub fn set_suspend_delays(&mut self, config: &cosmic_config::Config, delays: SuspendDelays) {
+        if let Err(err) = self.set_suspend_on_ac_time(config, to_ms(delays.ac)) {
+            tracing::error!(?err, "Failed to set suspend_on_ac_time");
+        }
+        if let Err(err) = self.set_suspend_on_battery_time(config, to_ms(delays.battery)) {
+            tracing::error!(?err, "Failed to set suspend_on_battery_time");
+        }
+    }
+}
+
+pub fn idle_config() -> Option<(cosmic_config::Config, IdleConfig)> {
+    let helper = cosmic_config::Config::new(IDLE_CONFIG_ID, IdleConfig::VERSION)
+        .inspect_err(|err| tracing::error!(?err, "Failed to open idle config"))
+        .ok()?;
+    let config = IdleConfig::get_entry(&helper).unwrap_or_else(|(errors, config)| {
+        for err in errors {
+            tracing::error!(?err, "Error loading idle config");
+        }
+        config
+    });
+    Some((helper, config))
+}
@@ -0,0 +1,138 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Input activity through the `ext-idle-notify-v1` Wayland protocol.
+//!
+//! Wayland clients can't see input outside their own surfaces, but the
+//! compositor reports when the session has gone without input for a while,
+//! and when input resumes. Idle inhibitors, such as a playing video, hold
+//! off the idle notification too.
+
+use cctk::sctk::reexports::{
+    client::{
+        self as wayland_client, Connection, Dispatch, QueueHandle,
+        globals::{GlobalListContents, registry_queue_init},
+        protocol::{wl_registry::WlRegistry, wl_seat::WlSeat},
+    },
+    protocols::ext::idle_notify::v1::client::{
+        ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+        ext_idle_notifier_v1::ExtIdleNotifierV1,
+    },
+};
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use futures::{
+    StreamExt,
+    channel::mpsc::{UnboundedSender, unbounded},
+};
+use std::{fmt::Debug, hash::Hash, time::Duration};
+
+struct State {
+    tx: UnboundedSender<bool>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: <ExtIdleNotifierV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let idle = match event {
+            ext_idle_notification_v1::Event::Idled => true,
+            ext_idle_notification_v1::Event::Resumed => false,
+            _ => return,
+        };
+        let _ = state.tx.unbounded_send(idle);
+    }
+}
+
+/// Blocks, sending idle state changes until the receiver is dropped.
+fn watch(timeout: Duration, tx: UnboundedSender<bool>) -> Result<(), String> {
+    let conn = Connection::connect_to_env().map_err(|err| err.to_string())?;
+    let (globals, mut queue) =
+        registry_queue_init::<State>(&conn).map_err(|err| err.to_string())?;
+    let qh = queue.handle();
+    let seat: WlSeat = globals
+        .bind(&qh, 1..=1, ())
+        .map_err(|err| err.to_string())?;
+    let notifier: ExtIdleNotifierV1 = globals
+        .bind(&qh, 1..=1, ())
+        .map_err(|err| err.to_string())?;
+    let timeout = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+    let _notification = notifier.get_idle_notification(timeout, &seat, &qh, ());
+
+    let mut state = State { tx };
+    while !state.tx.is_closed() {
+        queue
+            .blocking_dispatch(&mut state)
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Emits `true` once there has been no input for `timeout`, and `false` when
+/// input resumes.
+pub fn idle_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+    timeout: Duration,
+) -> iced::Subscription<bool> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(5, move |mut output| async move {
+            let (tx, mut rx) = unbounded();
+            std::thread::spawn(move || {
+                if let Err(err) = watch(timeout, tx) {
+                    tracing::warn!("Idle notifications are unavailable: {err}");
+                }
+            });
+            while let Some(idle) = rx.next().await {
+                if output.send(idle).await.is_err() {
+                    break;
+                }
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
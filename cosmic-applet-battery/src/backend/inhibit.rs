@@ -0,0 +1,70 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Keeps the screen awake with a logind idle inhibitor lock.
+//!
+//! logind hands out the lock as a file descriptor, held for as long as it
+//! stays open. The screen saver is asked as well where one runs, since it
+//! may blank the screen on its own; its inhibition lasts as long as the
+//! connection that asked for it. Both go away with the applet.
+
+use logind_zbus::manager::ManagerProxy;
+use std::sync::Arc;
+use zbus::{Connection, zvariant::OwnedFd};
+
+const SCREENSAVER: &str = "org.freedesktop.ScreenSaver";
+
+#[derive(Debug)]
+struct Locks {
+    _idle: OwnedFd,
+    /// The session bus connection holding the screen saver inhibition
+    _screensaver: Option<Connection>,
+}
+
+/// A held inhibitor, released when the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct Inhibitor(Arc<Locks>);
+
+pub async fn inhibit_idle(reason: String) -> zbus::Result<Inhibitor> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    let idle: OwnedFd = manager_proxy
+        .inner()
+        .call(
+            "Inhibit",
+            &("idle", "COSMIC Battery", reason.as_str(), "block"),
+        )
+        .await?;
+
+    let screensaver = match screensaver_inhibit(&reason).await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::debug!("Not inhibiting the screen saver: {err}");
+            None
+        }
+    };
+    Ok(Inhibitor(Arc::new(Locks {
+        _idle: idle,
+        _screensaver: screensaver,
+    })))
+}
+
+async fn screensaver_inhibit(reason: &str) -> zbus::Result<Option<Connection>> {
+    let conn = Connection::session().await?;
+    let dbus = zbus::fdo::DBusProxy::new(&conn).await?;
+    if !dbus
+        .name_has_owner(zbus::names::BusName::try_from(SCREENSAVER)?)
+        .await?
+    {
+        return Ok(None);
+    }
+    conn.call_method(
+        Some(SCREENSAVER),
+        "/org/freedesktop/ScreenSaver",
+        Some(SCREENSAVER),
+        "Inhibit",
+        &("com.system76.CosmicAppletBattery", reason),
+    )
+    .await?;
+    Ok(Some(conn))
+}
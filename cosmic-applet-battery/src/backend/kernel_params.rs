@@ -0,0 +1,55 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+
+const MODULE_PATH: &str = "/sys/module";
+
+/// Power related parameters of the GPU drivers, by module.
+const POWER_PARAMS: &[(&str, &[&str])] = &[
+    (
+        "i915",
+        &["enable_rc6", "enable_psr", "enable_fbc", "enable_dc"],
+    ),
+    ("amdgpu", &["runpm", "aspm", "dpm", "abmlevel"]),
+    ("radeon", &["runpm", "aspm", "dpm"]),
+    ("nouveau", &["runpm"]),
+];
+
+/// A module parameter as currently set, from `/sys/module/*/parameters`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelParam {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub value: String,
+}
+
+impl KernelParam {
+    /// The name as given on the kernel command line, e.g. `i915.enable_psr`.
+    pub fn key(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+/// Reads the power parameters of the loaded GPU modules. Parameters a driver
+/// version doesn't have, or doesn't expose, are left out.
+pub async fn kernel_params() -> Vec<KernelParam> {
+    let mut params = Vec::new();
+    for (module, names) in POWER_PARAMS {
+        let dir = Path::new(MODULE_PATH).join(module).join("parameters");
+        if !dir.exists() {
+            continue;
+        }
+        for name in *names {
+            let Ok(value) = tokio::fs::read_to_string(dir.join(name)).await else {
+                continue;
+            };
+            params.push(KernelParam {
+                module,
+                name,
+                value: value.trim().to_string(),
+            });
+        }
+    }
+    params
+}
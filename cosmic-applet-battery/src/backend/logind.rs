@@ -0,0 +1,220 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use futures::StreamExt;
+use logind_zbus::manager::ManagerProxy;
+use std::{fmt::Debug, hash::Hash, path::PathBuf, time::Duration};
+use zbus::{Connection, fdo::PropertiesProxy, names::InterfaceName};
+
+const SLEEP_CONF_DIRS: [&str; 2] = ["/usr/lib/systemd", "/etc/systemd"];
+// logind settings that may trigger a sleep
+const HANDLE_PROPERTIES: [&str; 4] = [
+    "HandleLidSwitch",
+    "HandleLidSwitchExternalPower",
+    "HandleSuspendKey",
+    "IdleAction",
+];
+
+/// The seat owning all hardware not explicitly assigned elsewhere.
+pub const PRIMARY_SEAT: &str = "seat0";
+
+/// The system power actions logind will carry out for this user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerActions {
+    pub suspend: bool,
+    pub hibernate: bool,
+    pub reboot: bool,
+    pub power_off: bool,
+}
+
+/// Asks one of the `Can*` methods, where `yes` and `challenge` allow the
+/// action while `no` and `na` rule it out.
+async fn can(manager_proxy: &ManagerProxy<'_>, method: &str) -> zbus::Result<bool> {
+    let answer: String = manager_proxy.inner().call(method, &()).await?;
+    Ok(!matches!(answer.as_str(), "no" | "na"))
+}
+
+pub async fn power_actions() -> zbus::Result<PowerActions> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    Ok(PowerActions {
+        suspend: can(&manager_proxy, "CanSuspend").await?,
+        hibernate: can(&manager_proxy, "CanHibernate").await?,
+        reboot: can(&manager_proxy, "CanReboot").await?,
+        power_off: can(&manager_proxy, "CanPowerOff").await?,
+    })
+}
+
+pub async fn hibernate() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.hibernate(false).await
+}
+
+pub async fn reboot() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.reboot(false).await
+}
+
+pub async fn power_off() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.power_off(false).await
+}
+
+pub async fn suspend() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.suspend(false).await
+}
+
+/// When a suspend-then-hibernate sleep moves on to hibernation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HibernateAfter {
+    /// `HibernateDelaySec` from sleep.conf
+    Delay(Duration),
+    /// Without a delay, systemd hibernates once the battery runs low
+    LowBattery,
+}
+
+/// Returns how the system will hibernate if logind is configured to
+/// suspend-then-hibernate.
+pub async fn suspend_then_hibernate() -> zbus::Result<Option<HibernateAfter>> {
+    let connection = Connection::system().await?;
+    let properties = PropertiesProxy::builder(&connection)
+        .destination("org.freedesktop.login1")?
+        .path("/org/freedesktop/login1")?
+        .build()
+        .await?;
+    let interface = InterfaceName::from_static_str_unchecked("org.freedesktop.login1.Manager");
+    let mut enabled = false;
+    for property in HANDLE_PROPERTIES {
+        let value = properties.get(interface.clone(), property).await?;
+        if String::try_from(value).is_ok_and(|action| action == "suspend-then-hibernate") {
+            enabled = true;
+            break;
+        }
+    }
+    if !enabled {
+        return Ok(None);
+    }
+    Ok(Some(match hibernate_delay().await {
+        Some(delay) => HibernateAfter::Delay(delay),
+        None => HibernateAfter::LowBattery,
+    }))
+}
+
+/// Reads `HibernateDelaySec` from sleep.conf and its drop-ins, where later
+/// files override earlier ones.
+async fn hibernate_delay() -> Option<Duration> {
+    let mut files = Vec::new();
+    for dir in SLEEP_CONF_DIRS {
+        files.push(PathBuf::from(dir).join("sleep.conf"));
+    }
+    let mut dropins = Vec::new();
+    for dir in SLEEP_CONF_DIRS {
+        let Ok(mut entries) = tokio::fs::read_dir(PathBuf::from(dir).join("sleep.conf.d")).await
+        else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.path().extension().is_some_and(|ext| ext == "conf") {
+                dropins.push(entry.path());
+            }
+        }
+    }
+    // drop-ins apply in file name order regardless of directory
+    dropins.sort_by_key(|path| path.file_name().map(ToOwned::to_owned));
+    files.extend(dropins);
+
+    let mut delay = None;
+    for file in files {
+        let Ok(contents) = tokio::fs::read_to_string(file).await else {
+            continue;
+        };
+        let mut in_sleep = false;
+        for line in contents.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_sleep = line == "[Sleep]";
+            } else if let Some(value) = line.strip_prefix("HibernateDelaySec=").filter(|_| in_sleep)
+            {
+                delay = parse_timespan(value);
+            }
+        }
+    }
+    delay
+}
+
+/// Parses a systemd time span such as `7200`, `90min` or `2h 30min`.
+fn parse_timespan(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == ' ')
+            .unwrap_or(rest.len());
+        let seconds = match &rest[..unit_len] {
+            "s" | "sec" => 1,
+            "m" | "min" => 60,
+            "h" | "hr" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        total += Duration::from_secs(number * seconds);
+        rest = rest[unit_len..].trim_start();
+    }
+    (!total.is_zero()).then_some(total)
+}
+
+/// Names of the seats known to logind.
+pub async fn seats() -> zbus::Result<Vec<String>> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    Ok(manager_proxy
+        .list_seats()
+        .await?
+        .into_iter()
+        .map(|seat| seat.id().to_string())
+        .collect())
+}
+
+/// Emits `true` when the system is about to sleep and `false` once it resumes.
+pub fn sleep_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<bool> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(5, move |mut output| async move {
+            if let Err(err) = watch_sleep(&mut output).await {
+                tracing::error!("logind sleep watch failed: {err}");
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+async fn watch_sleep(output: &mut futures::channel::mpsc::Sender<bool>) -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    let mut prepare_for_sleep = manager_proxy.receive_prepare_for_sleep().await?;
+    while let Some(signal) = prepare_for_sleep.next().await {
+        let start = signal.args()?.start;
+        if output.send(start).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::sysfs::write_privileged;
+
+const MEM_SLEEP_PATH: &str = "/sys/power/mem_sleep";
+
+/// Suspend-to-RAM variants exposed by the kernel in `/sys/power/mem_sleep`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemSleep {
+    /// S0ix / modern standby
+    S2Idle,
+    Shallow,
+    /// S3 / traditional suspend to RAM
+    Deep,
+}
+
+impl MemSleep {
+    fn from_kernel(name: &str) -> Option<Self> {
+        match name {
+            "s2idle" => Some(Self::S2Idle),
+            "shallow" => Some(Self::Shallow),
+            "deep" => Some(Self::Deep),
+            _ => None,
+        }
+    }
+
+    pub fn as_kernel(self) -> &'static str {
+        match self {
+            Self::S2Idle => "s2idle",
+            Self::Shallow => "shallow",
+            Self::Deep => "deep",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemSleepState {
+    pub current: MemSleep,
+    pub available: Vec<MemSleep>,
+}
+
+impl MemSleepState {
+    /// Parses the kernel format, where the active entry is bracketed:
+    /// `s2idle [deep]`.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut current = None;
+        let mut available = Vec::new();
+        for word in contents.split_whitespace() {
+            let (name, active) = match word.strip_prefix('[').and_then(|w| w.strip_suffix(']')) {
+                Some(name) => (name, true),
+                None => (word, false),
+            };
+            let Some(state) = MemSleep::from_kernel(name) else {
+                continue;
+            };
+            if active {
+                current = Some(state);
+            }
+            available.push(state);
+        }
+
+        Some(Self {
+            current: current?,
+            available,
+        })
+    }
+}
+
+pub async fn get_mem_sleep() -> Option<MemSleepState> {
+    let contents = tokio::fs::read_to_string(MEM_SLEEP_PATH).await.ok()?;
+    MemSleepState::parse(&contents)
+}
+
+// takes effect on the next suspend
+pub async fn set_mem_sleep(state: MemSleep) -> Result<MemSleepState, String> {
+    write_privileged(MEM_SLEEP_PATH, state.as_kernel(), |helper| async move {
+        helper.set_mem_sleep(state.as_kernel()).await
+    })
+    .await?;
+    get_mem_sleep()
+        .await
+        .ok_or_else(|| "Failed to read back mem_sleep".to_string())
+}
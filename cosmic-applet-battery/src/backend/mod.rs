@@ -5,14 +5,41 @@ use cosmic::{
     iced::{self, Subscription, futures::SinkExt},
     iced_futures::stream,
 };
+use futures::StreamExt;
 use std::{fmt::Debug, hash::Hash};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use zbus::{Connection, Result};
+use zbus::{Connection, Result, fdo::PropertiesProxy};
 
 use self::{power_daemon::PowerDaemonProxy, power_profiles::PowerProfilesProxy};
 
+pub mod backlight;
+mod battery_limit;
+pub mod calendar;
+pub mod cpufreq;
+pub mod ddc;
+pub mod device;
+pub mod dock;
+pub mod gamma;
+mod helper;
+pub mod hwmon;
+pub mod idle;
+pub mod idle_notify;
+pub mod inhibit;
+pub mod kernel_params;
+pub mod logind;
+pub mod mem_sleep;
+pub mod modem;
+pub mod notifications;
+pub mod nut;
 mod power_daemon;
 mod power_profiles;
+pub mod screensaver;
+mod sysfs;
+mod upower;
+pub mod upower_device;
+pub mod usb;
+pub mod wake_on_lan;
+pub mod wakeup;
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
 pub enum Power {
@@ -22,6 +49,26 @@ pub enum Power {
     Performance,
 }
 
+impl Power {
+    /// Name used in the config and exports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Battery => "battery",
+            Self::Balanced => "balanced",
+            Self::Performance => "performance",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "battery" => Some(Self::Battery),
+            "balanced" => Some(Self::Balanced),
+            "performance" => Some(Self::Performance),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Backend<'a> {
     S76PowerDaemon(PowerDaemonProxy<'a>),
@@ -33,13 +80,29 @@ pub enum BackendType {
     #[default]
     S76PowerDaemon,
     PowerProfilesDaemon,
+    /// power-profiles-daemon before 0.20, under its original name
+    LegacyPowerProfilesDaemon,
 }
 
 impl BackendType {
     fn next(self) -> Option<Self> {
         match self {
             Self::S76PowerDaemon => Some(Self::PowerProfilesDaemon),
-            Self::PowerProfilesDaemon => None,
+            Self::PowerProfilesDaemon => Some(Self::LegacyPowerProfilesDaemon),
+            Self::LegacyPowerProfilesDaemon => None,
+        }
+    }
+
+    /// The bus name and object path of power-profiles-daemon.
+    fn ppd_address(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::LegacyPowerProfilesDaemon => {
+                ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles")
+            }
+            _ => (
+                "org.freedesktop.UPower.PowerProfiles",
+                "/org/freedesktop/UPower/PowerProfiles",
+            ),
         }
     }
 }
@@ -52,9 +115,16 @@ pub async fn get_power_backend<'a>(
         BackendType::S76PowerDaemon => PowerDaemonProxy::new(conn)
             .await
             .map(Backend::S76PowerDaemon),
-        BackendType::PowerProfilesDaemon => PowerProfilesProxy::new(conn)
-            .await
-            .map(Backend::PowerProfilesDaemon),
+        BackendType::PowerProfilesDaemon | BackendType::LegacyPowerProfilesDaemon => {
+            let (destination, path) = backend_type.ppd_address();
+            PowerProfilesProxy::builder(conn)
+                .destination(destination)?
+                .path(path)?
+                .interface(destination)?
+                .build()
+                .await
+                .map(Backend::PowerProfilesDaemon)
+        }
     }
 }
 
@@ -181,25 +251,76 @@ async fn start_listening(
                 }
             };
 
-            match rx.recv().await {
-                Some(PowerProfileRequest::Get) => {
+            let mut changes = match profile_changes(&conn, &backend_type).await {
+                Ok(changes) => changes,
+                Err(err) => {
+                    tracing::debug!("Not following power profile changes: {err}");
+                    futures::stream::pending().boxed()
+                }
+            };
+
+            tokio::select! {
+                request = rx.recv() => match request {
+                    Some(PowerProfileRequest::Get) => {
+                        if let Ok(profile) = get_power_profile(backend).await {
+                            _ = output.send(PowerProfileUpdate::Update { profile }).await;
+                        }
+                        State::Waiting(conn, rx, backend_type)
+                    }
+                    Some(PowerProfileRequest::Set(profile)) => {
+                        let _ = set_power_profile(backend, profile).await;
+                        _ = output.send(PowerProfileUpdate::Update { profile }).await;
+                        State::Waiting(conn, rx, backend_type)
+                    }
+                    None => State::Finished,
+                },
+                Some(()) = changes.next() => {
+                    // switched by another program, or a hotkey
                     if let Ok(profile) = get_power_profile(backend).await {
                         _ = output.send(PowerProfileUpdate::Update { profile }).await;
                     }
                     State::Waiting(conn, rx, backend_type)
                 }
-                Some(PowerProfileRequest::Set(profile)) => {
-                    let _ = set_power_profile(backend, profile).await;
-                    _ = output.send(PowerProfileUpdate::Update { profile }).await;
-                    State::Waiting(conn, rx, backend_type)
-                }
-                None => State::Finished,
             }
         }
         State::Finished => iced::futures::future::pending().await,
     }
 }
 
+/// Yields whenever the active profile changes.
+async fn profile_changes(
+    conn: &Connection,
+    backend_type: &BackendType,
+) -> Result<futures::stream::BoxStream<'static, ()>> {
+    Ok(match backend_type {
+        BackendType::S76PowerDaemon => PowerDaemonProxy::new(conn)
+            .await?
+            .receive_power_profile_switch()
+            .await?
+            .map(|_| ())
+            .boxed(),
+        BackendType::PowerProfilesDaemon | BackendType::LegacyPowerProfilesDaemon => {
+            let (destination, path) = backend_type.ppd_address();
+            // signals only, a property stream would also yield the current value
+            PropertiesProxy::builder(conn)
+                .destination(destination)?
+                .path(path)?
+                .build()
+                .await?
+                .receive_properties_changed()
+                .await?
+                .filter(|signal| {
+                    let active = signal
+                        .args()
+                        .is_ok_and(|args| args.changed_properties().contains_key("ActiveProfile"));
+                    std::future::ready(active)
+                })
+                .map(|_| ())
+                .boxed()
+        }
+    })
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum PowerProfileRequest {
     Get,
@@ -219,8 +340,9 @@ pub async fn get_charging_limit() -> anyhow::Result<bool> {
         if let Ok(backend) = get_power_backend(&conn, &BackendType::S76PowerDaemon).await {
             match backend {
                 Backend::S76PowerDaemon(proxy) => {
-                    if let Ok((start, end)) = proxy.get_charge_thresholds().await {
-                        return Ok(start > 0 || end > 0);
+                    // cleared thresholds read back as (0, 100)
+                    if let Ok((_, end)) = proxy.get_charge_thresholds().await {
+                        return Ok(end < 100);
                     }
                 }
                 Backend::PowerProfilesDaemon(_) => {
@@ -229,24 +351,63 @@ pub async fn get_charging_limit() -> anyhow::Result<bool> {
             };
         }
     }
+    // without system76-power, from sysfs
+    if let Some((_, end)) = battery_limit::charge_thresholds().await {
+        return Ok(end < 100);
+    }
     anyhow::bail!("Unsupported")
 }
 
-// set battery charging thresholds via s76 power_daemon
-pub async fn set_charging_limit() -> Result<()> {
-    if let Ok(conn) = Connection::system().await {
-        if let Ok(backend) = get_power_backend(&conn, &BackendType::S76PowerDaemon).await {
-            match backend {
-                Backend::S76PowerDaemon(proxy) => {
-                    let _ = proxy.set_charge_thresholds(&(70, 80)).await;
-                }
-                Backend::PowerProfilesDaemon(_) => {
-                    tracing::info!(
-                        "Setting charging limit via Power Profiles Daemon is not supported."
-                    );
-                }
-            };
+// set battery charging thresholds via s76 power_daemon, resuming charging
+// 10% below `end`
+pub async fn set_charging_limit(end: u8) -> Result<()> {
+    let _ = set_charge_thresholds((end.saturating_sub(10), end)).await;
+    Ok(())
+}
+
+// remove the battery charging thresholds via s76 power_daemon
+pub async fn clear_charging_limit() -> anyhow::Result<()> {
+    set_charge_thresholds((0, 100)).await
+}
+
+// read the raw (start, end) battery charging thresholds from s76 power_daemon,
+// or from sysfs without it
+pub async fn get_charge_thresholds() -> anyhow::Result<(u8, u8)> {
+    let conn = Connection::system().await?;
+    match get_power_backend(&conn, &BackendType::S76PowerDaemon).await? {
+        Backend::S76PowerDaemon(proxy) => match proxy.get_charge_thresholds().await {
+            Ok(thresholds) => Ok(thresholds),
+            Err(err) => battery_limit::charge_thresholds()
+                .await
+                .ok_or_else(|| err.into()),
+        },
+        Backend::PowerProfilesDaemon(_) => {
+            anyhow::bail!("Power Profiles Daemon does not support charge thresholds.")
         }
     }
-    Ok(())
+}
+
+pub async fn set_charge_thresholds(thresholds: (u8, u8)) -> anyhow::Result<()> {
+    let conn = Connection::system().await?;
+    match get_power_backend(&conn, &BackendType::S76PowerDaemon).await? {
+        Backend::S76PowerDaemon(proxy) => match proxy.set_charge_thresholds(&thresholds).await {
+            Ok(()) => Ok(()),
+            Err(err) if battery_limit::charge_thresholds().await.is_some() => {
+                tracing::debug!("Setting charge thresholds through sysfs: {err}");
+                Ok(battery_limit::set_charge_thresholds(thresholds).await?)
+            }
+            Err(err) => Err(err.into()),
+        },
+        Backend::PowerProfilesDaemon(_) => {
+            tracing::info!("Setting charging limit via Power Profiles Daemon is not supported.");
+            Ok(())
+        }
+    }
+}
+
+// switch to a 40-50% maintenance band, returning the thresholds it replaced
+pub async fn enable_storage_mode() -> anyhow::Result<(u8, u8)> {
+    let previous = get_charge_thresholds().await?;
+    set_charge_thresholds((40, 50)).await?;
+    Ok(previous)
 }
@@ -0,0 +1,144 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The built-in WWAN modem, through ModemManager.
+
+use zbus::{Connection, fdo::ObjectManagerProxy, proxy};
+
+const MODEM_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem";
+// `MMModemState`
+const STATE_DISABLED: i32 = 3;
+const STATE_REGISTERED: i32 = 8;
+const STATE_CONNECTED: i32 = 11;
+// `MMModemAccessTechnology` bits
+const ACCESS_5GNR: u32 = 1 << 15;
+const ACCESS_LTE: u32 = 1 << 14;
+const ACCESS_3G: u32 = 0b11_1111_1110_0000;
+const ACCESS_2G: u32 = 0b1_1110;
+
+#[proxy(
+    interface = "org.freedesktop.ModemManager1.Modem",
+    default_service = "org.freedesktop.ModemManager1"
+)]
+trait Modem {
+    /// Enable method
+    fn enable(&self, enable: bool) -> zbus::Result<()>;
+
+    /// AccessTechnologies property
+    #[zbus(property)]
+    fn access_technologies(&self) -> zbus::Result<u32>;
+
+    /// SignalQuality property, in percent, and whether it's recent
+    #[zbus(property)]
+    fn signal_quality(&self) -> zbus::Result<(u32, bool)>;
+
+    /// State property
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<i32>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModemState {
+    Disabled,
+    Searching,
+    Registered,
+    Connected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModemStatus {
+    pub path: String,
+    pub state: ModemState,
+    /// Most capable access technology in use, e.g. `LTE`
+    pub technology: Option<&'static str>,
+    /// Signal strength from 0 to 4
+    pub bars: u32,
+}
+
+impl ModemStatus {
+    pub fn enabled(&self) -> bool {
+        self.state != ModemState::Disabled
+    }
+}
+
+fn technology(access: u32) -> Option<&'static str> {
+    if access & ACCESS_5GNR != 0 {
+        Some("5G")
+    } else if access & ACCESS_LTE != 0 {
+        Some("LTE")
+    } else if access & ACCESS_3G != 0 {
+        Some("3G")
+    } else if access & ACCESS_2G != 0 {
+        Some("2G")
+    } else {
+        None
+    }
+}
+
+async fn first_modem(conn: &Connection) -> zbus::Result<Option<ModemProxy<'static>>> {
+    let objects = ObjectManagerProxy::builder(conn)
+        .destination("org.freedesktop.ModemManager1")?
+        .path("/org/freedesktop/ModemManager1")?
+        .build()
+        .await?
+        .get_managed_objects()
+        .await?;
+    let Some(path) = objects.into_iter().find_map(|(path, interfaces)| {
+        interfaces
+            .keys()
+            .any(|name| name.as_str() == MODEM_INTERFACE)
+            .then_some(path)
+    }) else {
+        return Ok(None);
+    };
+    ModemProxy::builder(conn)
+        .path(path)?
+        .build()
+        .await
+        .map(Some)
+}
+
+/// The first modem's state, or `None` without ModemManager or a modem.
+pub async fn modem_status() -> Option<ModemStatus> {
+    let read = async {
+        let conn = Connection::system().await?;
+        let Some(modem) = first_modem(&conn).await? else {
+            return Ok(None);
+        };
+        let state = match modem.state().await? {
+            i32::MIN..=STATE_DISABLED => ModemState::Disabled,
+            STATE_CONNECTED => ModemState::Connected,
+            STATE_REGISTERED.. => ModemState::Registered,
+            _ => ModemState::Searching,
+        };
+        let (quality, _) = modem.signal_quality().await.unwrap_or_default();
+        zbus::Result::Ok(Some(ModemStatus {
+            path: modem.inner().path().to_string(),
+            state,
+            technology: technology(modem.access_technologies().await.unwrap_or_default()),
+            bars: (quality.min(100) + 24) / 25,
+        }))
+    };
+    match read.await {
+        Ok(status) => status,
+        Err(err) => {
+            tracing::debug!("ModemManager is unavailable: {err}");
+            None
+        }
+    }
+}
+
+/// Powers the modem up or down, returning its new state.
+pub async fn set_modem_enabled(path: String, enable: bool) -> Result<Option<ModemStatus>, String> {
+    let conn = Connection::system().await.map_err(|err| err.to_string())?;
+    ModemProxy::builder(&conn)
+        .path(path)
+        .map_err(|err| err.to_string())?
+        .build()
+        .await
+        .map_err(|err| err.to_string())?
+        .enable(enable)
+        .await
+        .map_err(|err| format!("Failed to switch the modem: {err}"))?;
+    Ok(modem_status().await)
+}
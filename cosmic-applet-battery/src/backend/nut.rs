@@ -0,0 +1,108 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Input power readings from a UPS managed by Network UPS Tools.
+//!
+//! NUT has no D-Bus interface, so this talks to the local `upsd` over its
+//! line-based network protocol.
+
+use std::time::Duration;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+const UPSD_ADDRESS: &str = "127.0.0.1:3493";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerQuality {
+    /// Name of the UPS in `ups.conf`
+    pub ups: String,
+    /// Volts
+    pub input_voltage: Option<f64>,
+    /// Hertz
+    pub input_frequency: Option<f64>,
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    async fn request(&mut self, command: &str) -> std::io::Result<()> {
+        let stream = self.reader.get_mut();
+        stream.write_all(command.as_bytes()).await?;
+        stream.write_all(b"\n").await
+    }
+
+    async fn line(&mut self) -> std::io::Result<Option<String>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end().to_string()))
+    }
+
+    /// Names of the UPSes `upsd` serves.
+    async fn list_ups(&mut self) -> std::io::Result<Vec<String>> {
+        self.request("LIST UPS").await?;
+        let mut ups = Vec::new();
+        while let Some(line) = self.line().await? {
+            if line.starts_with("END LIST") || line.starts_with("ERR") {
+                break;
+            }
+            // UPS <name> "<description>"
+            if let Some(name) = line
+                .strip_prefix("UPS ")
+                .and_then(|rest| rest.split_whitespace().next())
+            {
+                ups.push(name.to_string());
+            }
+        }
+        Ok(ups)
+    }
+
+    /// A numeric variable, or `None` if the UPS doesn't report it.
+    async fn get_var(&mut self, ups: &str, var: &str) -> std::io::Result<Option<f64>> {
+        self.request(&format!("GET VAR {ups} {var}")).await?;
+        // VAR <ups> <var> "<value>", or ERR VAR-NOT-SUPPORTED
+        let Some(line) = self.line().await? else {
+            return Ok(None);
+        };
+        Ok(line
+            .strip_prefix("VAR ")
+            .and_then(|rest| rest.split_once('"'))
+            .and_then(|(_, value)| value.trim_end_matches('"').parse().ok()))
+    }
+}
+
+async fn query() -> std::io::Result<Option<PowerQuality>> {
+    let stream = TcpStream::connect(UPSD_ADDRESS).await?;
+    let mut client = Client {
+        reader: BufReader::new(stream),
+    };
+    let Some(ups) = client.list_ups().await?.into_iter().next() else {
+        return Ok(None);
+    };
+    let input_voltage = client.get_var(&ups, "input.voltage").await?;
+    let input_frequency = client.get_var(&ups, "input.frequency").await?;
+    let _ = client.request("LOGOUT").await;
+    Ok(Some(PowerQuality {
+        ups,
+        input_voltage,
+        input_frequency,
+    }))
+}
+
+/// Reads the first UPS's input power, or `None` without a running `upsd`.
+pub async fn power_quality() -> Option<PowerQuality> {
+    match tokio::time::timeout(TIMEOUT, query()).await {
+        Ok(Ok(quality)) => quality,
+        Ok(Err(err)) => {
+            tracing::debug!("NUT is unavailable: {err}");
+            None
+        }
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use futures::StreamExt;
+use std::{fmt::Debug, hash::Hash};
+use zbus::{Connection, MatchRule, MessageStream, message::Type};
+
+// both share the `ActiveChanged(b)` signal
+const INTERFACES: [&str; 2] = ["org.freedesktop.ScreenSaver", "org.gnome.ScreenSaver"];
+const SCREENSAVER: &str = "org.freedesktop.ScreenSaver";
+
+/// Whether a screen saver that can lock the screen is running.
+pub async fn can_lock() -> zbus::Result<bool> {
+    let conn = Connection::session().await?;
+    let dbus = zbus::fdo::DBusProxy::new(&conn).await?;
+    Ok(dbus
+        .name_has_owner(zbus::names::BusName::try_from(SCREENSAVER)?)
+        .await?)
+}
+
+pub async fn lock() -> zbus::Result<()> {
+    let conn = Connection::session().await?;
+    conn.call_method(
+        Some(SCREENSAVER),
+        "/org/freedesktop/ScreenSaver",
+        Some(SCREENSAVER),
+        "Lock",
+        &(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Emits `true` when the screen saver activates and the screen blanks.
+pub fn screensaver_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<bool> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(5, move |mut output| async move {
+            if let Err(err) = watch_active(&mut output).await {
+                tracing::error!("Screen saver watch failed: {err}");
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+async fn watch_active(output: &mut futures::channel::mpsc::Sender<bool>) -> zbus::Result<()> {
+    let conn = Connection::session().await?;
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .member("ActiveChanged")?
+        .build();
+    let mut messages = MessageStream::for_match_rule(rule, &conn, None).await?;
+    while let Some(message) = messages.next().await {
+        let Ok(message) = message else {
+            continue;
+        };
+        let header = message.header();
+        if !header
+            .interface()
+            .is_some_and(|interface| INTERFACES.contains(&interface.as_str()))
+        {
+            continue;
+        }
+        let Ok(active) = message.body().deserialize::<bool>() else {
+            continue;
+        };
+        if output.send(active).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
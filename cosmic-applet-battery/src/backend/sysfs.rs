@@ -0,0 +1,33 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{future::Future, io, path::Path};
+use zbus::Connection;
+
+use super::helper::BatteryHelperProxy;
+
+/// Writes `value` to a sysfs attribute, falling back to the polkit-authorized
+/// helper service when the attribute isn't writable by the current user.
+pub async fn write_privileged<F, Fut>(
+    path: impl AsRef<Path>,
+    value: &str,
+    escalate: F,
+) -> Result<(), String>
+where
+    F: FnOnce(BatteryHelperProxy<'static>) -> Fut,
+    Fut: Future<Output = zbus::Result<()>>,
+{
+    let path = path.as_ref();
+    match tokio::fs::write(path, value).await {
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {}
+        res => return res.map_err(|err| format!("Failed to write {}: {err}", path.display())),
+    }
+
+    let conn = Connection::system().await.map_err(|err| err.to_string())?;
+    let helper = BatteryHelperProxy::new(&conn)
+        .await
+        .map_err(|err| err.to_string())?;
+    escalate(helper)
+        .await
+        .map_err(|err| format!("Helper failed to write {}: {err}", path.display()))
+}
@@ -0,0 +1,56 @@
+//! # DBus interface proxy for: `org.freedesktop.UPower`
+//!
+//! This code was generated by `zbus-xmlgen` `5.1.0` from DBus introspection data.
+//! Source: `Interface '/org/freedesktop/UPower' from service 'org.freedesktop.UPower' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the
+//! [Writing a client proxy](https://dbus.pages.freedesktop.org/zbus/client.html)
+//! section of the zbus documentation.
+//!
+
+use zbus::{
+    proxy,
+    zvariant::{ObjectPath, OwnedObjectPath},
+};
+
+#[proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+pub trait UPower {
+    /// EnumerateDevices method
+    fn enumerate_devices(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    /// GetCriticalAction method
+    fn get_critical_action(&self) -> zbus::Result<String>;
+
+    /// GetDisplayDevice method
+    fn get_display_device(&self) -> zbus::Result<OwnedObjectPath>;
+
+    /// DeviceAdded signal
+    #[zbus(signal)]
+    fn device_added(&self, device: ObjectPath<'_>) -> zbus::Result<()>;
+
+    /// DeviceRemoved signal
+    #[zbus(signal)]
+    fn device_removed(&self, device: ObjectPath<'_>) -> zbus::Result<()>;
+
+    /// DaemonVersion property
+    #[zbus(property)]
+    fn daemon_version(&self) -> zbus::Result<String>;
+
+    /// LidIsClosed property
+    #[zbus(property)]
+    fn lid_is_closed(&self) -> zbus::Result<bool>;
+
+    /// LidIsPresent property
+    #[zbus(property)]
+    fn lid_is_present(&self) -> zbus::Result<bool>;
+
+    /// OnBattery property
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
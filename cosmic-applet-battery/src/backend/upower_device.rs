@@ -0,0 +1,287 @@
+//! # DBus interface proxy for: `org.freedesktop.UPower.Device`
+//!
+//! This code was generated by `zbus-xmlgen` `5.1.0` from DBus introspection data.
+//! Source: `Interface '/org/freedesktop/UPower/devices/DisplayDevice' from service 'org.freedesktop.UPower' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the
+//! [Writing a client proxy](https://dbus.pages.freedesktop.org/zbus/client.html)
+//! section of the zbus documentation.
+//!
+
+use zbus::proxy;
+
+#[proxy(
+    interface = "org.freedesktop.UPower.Device",
+    default_service = "org.freedesktop.UPower"
+)]
+pub trait Device {
+    /// GetHistory method
+    fn get_history(
+        &self,
+        type_: &str,
+        timespan: u32,
+        resolution: u32,
+    ) -> zbus::Result<Vec<(u32, f64, u32)>>;
+
+    /// GetStatistics method
+    fn get_statistics(&self, type_: &str) -> zbus::Result<Vec<(f64, f64)>>;
+
+    /// Refresh method
+    fn refresh(&self) -> zbus::Result<()>;
+
+    /// BatteryLevel property
+    #[zbus(property)]
+    fn battery_level(&self) -> zbus::Result<u32>;
+
+    /// Capacity property
+    #[zbus(property)]
+    fn capacity(&self) -> zbus::Result<f64>;
+
+    /// ChargeCycles property
+    #[zbus(property)]
+    fn charge_cycles(&self) -> zbus::Result<i32>;
+
+    /// Energy property
+    #[zbus(property)]
+    fn energy(&self) -> zbus::Result<f64>;
+
+    /// EnergyEmpty property
+    #[zbus(property)]
+    fn energy_empty(&self) -> zbus::Result<f64>;
+
+    /// EnergyFull property
+    #[zbus(property)]
+    fn energy_full(&self) -> zbus::Result<f64>;
+
+    /// EnergyFullDesign property
+    #[zbus(property)]
+    fn energy_full_design(&self) -> zbus::Result<f64>;
+
+    /// EnergyRate property
+    #[zbus(property)]
+    fn energy_rate(&self) -> zbus::Result<f64>;
+
+    /// HasHistory property
+    #[zbus(property)]
+    fn has_history(&self) -> zbus::Result<bool>;
+
+    /// HasStatistics property
+    #[zbus(property)]
+    fn has_statistics(&self) -> zbus::Result<bool>;
+
+    /// IconName property
+    #[zbus(property)]
+    fn icon_name(&self) -> zbus::Result<String>;
+
+    /// IsPresent property
+    #[zbus(property)]
+    fn is_present(&self) -> zbus::Result<bool>;
+
+    /// IsRechargeable property
+    #[zbus(property)]
+    fn is_rechargeable(&self) -> zbus::Result<bool>;
+
+    /// Luminosity property
+    #[zbus(property)]
+    fn luminosity(&self) -> zbus::Result<f64>;
+
+    /// Model property
+    #[zbus(property)]
+    fn model(&self) -> zbus::Result<String>;
+
+    /// NativePath property
+    #[zbus(property)]
+    fn native_path(&self) -> zbus::Result<String>;
+
+    /// Online property
+    #[zbus(property)]
+    fn online(&self) -> zbus::Result<bool>;
+
+    /// Percentage property
+    #[zbus(property)]
+    fn percentage(&self) -> zbus::Result<f64>;
+
+    /// PowerSupply property
+    #[zbus(property)]
+    fn power_supply(&self) -> zbus::Result<bool>;
+
+    /// Serial property
+    #[zbus(property)]
+    fn serial(&self) -> zbus::Result<String>;
+
+    /// State property
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+
+    /// Technology property
+    #[zbus(property)]
+    fn technology(&self) -> zbus::Result<u32>;
+
+    /// Temperature property
+    #[zbus(property)]
+    fn temperature(&self) -> zbus::Result<f64>;
+
+    /// TimeToEmpty property
+    #[zbus(property)]
+    fn time_to_empty(&self) -> zbus::Result<i64>;
+
+    /// TimeToFull property
+    #[zbus(property)]
+    fn time_to_full(&self) -> zbus::Result<i64>;
+
+    /// Type property
+    #[zbus(property)]
+    fn type_(&self) -> zbus::Result<u32>;
+
+    /// UpdateTime property
+    #[zbus(property)]
+    fn update_time(&self) -> zbus::Result<u64>;
+
+    /// Vendor property
+    #[zbus(property)]
+    fn vendor(&self) -> zbus::Result<String>;
+
+    /// Voltage property
+    #[zbus(property)]
+    fn voltage(&self) -> zbus::Result<f64>;
+
+    /// WarningLevel property
+    #[zbus(property)]
+    fn warning_level(&self) -> zbus::Result<u32>;
+}
+
+/// `org.freedesktop.UPower.Device.Type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceKind {
+    #[default]
+    Unknown,
+    LinePower,
+    Battery,
+    Ups,
+    Monitor,
+    Mouse,
+    Keyboard,
+    Pda,
+    Phone,
+    Tablet,
+    GamingInput,
+    Headset,
+    Speakers,
+    Headphones,
+    Other(u32),
+}
+
+impl From<u32> for DeviceKind {
+    fn from(kind: u32) -> Self {
+        match kind {
+            0 => Self::Unknown,
+            1 => Self::LinePower,
+            2 => Self::Battery,
+            3 => Self::Ups,
+            4 => Self::Monitor,
+            5 => Self::Mouse,
+            6 => Self::Keyboard,
+            7 => Self::Pda,
+            8 => Self::Phone,
+            10 => Self::Tablet,
+            12 => Self::GamingInput,
+            17 => Self::Headset,
+            18 => Self::Speakers,
+            19 => Self::Headphones,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl DeviceKind {
+    /// Name used by `upower --dump`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::LinePower => "line-power",
+            Self::Battery => "battery",
+            Self::Ups => "ups",
+            Self::Monitor => "monitor",
+            Self::Mouse => "mouse",
+            Self::Keyboard => "keyboard",
+            Self::Pda => "pda",
+            Self::Phone => "phone",
+            Self::Tablet => "tablet",
+            Self::GamingInput => "gaming-input",
+            Self::Headset => "headset",
+            Self::Speakers => "speakers",
+            Self::Headphones => "headphones",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+/// `org.freedesktop.UPower.Device.State`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatteryState {
+    #[default]
+    Unknown,
+    Charging,
+    Discharging,
+    Empty,
+    FullyCharged,
+    PendingCharge,
+    PendingDischarge,
+}
+
+impl From<u32> for BatteryState {
+    fn from(state: u32) -> Self {
+        match state {
+            1 => Self::Charging,
+            2 => Self::Discharging,
+            3 => Self::Empty,
+            4 => Self::FullyCharged,
+            5 => Self::PendingCharge,
+            6 => Self::PendingDischarge,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// `org.freedesktop.UPower.Device.WarningLevel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WarningLevel {
+    #[default]
+    Unknown,
+    None,
+    /// Only reported for UPS devices
+    Discharging,
+    Low,
+    Critical,
+    /// UPower is about to run the critical power action
+    Action,
+}
+
+impl From<u32> for WarningLevel {
+    fn from(level: u32) -> Self {
+        match level {
+            1 => Self::None,
+            2 => Self::Discharging,
+            3 => Self::Low,
+            4 => Self::Critical,
+            5 => Self::Action,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl BatteryState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Charging => "charging",
+            Self::Discharging => "discharging",
+            Self::Empty => "empty",
+            Self::FullyCharged => "fully-charged",
+            Self::PendingCharge => "pending-charge",
+            Self::PendingDischarge => "pending-discharge",
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::sysfs::write_privileged;
+use std::path::Path;
+
+const USB_DEVICES_PATH: &str = "/sys/bus/usb/devices";
+
+/// Rough idle draw avoided by suspending an unused device, for the estimate.
+pub const AUTOSUSPEND_SAVING_MW: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbDevice {
+    /// sysfs device name, e.g. `1-2`
+    pub id: String,
+    pub product: String,
+    /// `power/control` is `auto` rather than `on`
+    pub autosuspend: bool,
+}
+
+async fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    Some(contents.trim().to_string())
+}
+
+pub async fn usb_devices() -> Vec<UsbDevice> {
+    let mut devices = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(USB_DEVICES_PATH).await else {
+        return devices;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(id) = entry.file_name().into_string() else {
+            continue;
+        };
+        // interfaces (`1-2:1.0`) and root hubs (`usb1`) are left alone
+        if id.contains(':') || id.starts_with("usb") {
+            continue;
+        }
+        let path = entry.path();
+        let Some(control) = read_trimmed(path.join("power/control")).await else {
+            continue;
+        };
+        let product = read_trimmed(path.join("product"))
+            .await
+            .unwrap_or_else(|| id.clone());
+        devices.push(UsbDevice {
+            id,
+            product,
+            autosuspend: control == "auto",
+        });
+    }
+    devices.sort_by(|a, b| a.id.cmp(&b.id));
+    devices
+}
+
+pub async fn set_autosuspend(id: String, autosuspend: bool) -> Result<Vec<UsbDevice>, String> {
+    let control = if autosuspend { "auto" } else { "on" };
+    let path = Path::new(USB_DEVICES_PATH).join(&id).join("power/control");
+    write_privileged(path, control, |helper| async move {
+        helper.set_usb_power_control(&id, control).await
+    })
+    .await?;
+    Ok(usb_devices().await)
+}
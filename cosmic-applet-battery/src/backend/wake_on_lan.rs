@@ -0,0 +1,82 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+use zbus::{Connection, proxy, zvariant::OwnedObjectPath};
+
+use super::helper::BatteryHelperProxy;
+
+const NET_PATH: &str = "/sys/class/net";
+// `NMDeviceType` of wired Ethernet
+const NM_DEVICE_TYPE_ETHERNET: u32 = 1;
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    /// GetDevices method
+    fn get_devices(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Device",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait NetworkDevice {
+    /// DeviceType property
+    #[zbus(property)]
+    fn device_type(&self) -> zbus::Result<u32>;
+
+    /// Interface property
+    #[zbus(property)]
+    fn interface(&self) -> zbus::Result<String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakeOnLan {
+    pub interface: String,
+    pub enabled: bool,
+}
+
+async fn read_wakeup(interface: &str) -> Option<bool> {
+    let path = Path::new(NET_PATH)
+        .join(interface)
+        .join("device/power/wakeup");
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    Some(contents.trim() == "enabled")
+}
+
+/// Wake-on-LAN state of the first Ethernet interface that supports wakeups.
+pub async fn wake_on_lan() -> zbus::Result<Option<WakeOnLan>> {
+    let conn = Connection::system().await?;
+    let network_manager = NetworkManagerProxy::new(&conn).await?;
+    for path in network_manager.get_devices().await? {
+        let device = NetworkDeviceProxy::builder(&conn)
+            .path(path)?
+            .build()
+            .await?;
+        if device.device_type().await? != NM_DEVICE_TYPE_ETHERNET {
+            continue;
+        }
+        let interface = device.interface().await?;
+        if let Some(enabled) = read_wakeup(&interface).await {
+            return Ok(Some(WakeOnLan { interface, enabled }));
+        }
+    }
+    Ok(None)
+}
+
+// always goes through the helper, since ethtool needs root as well
+pub async fn set_wake_on_lan(interface: String, enabled: bool) -> Result<WakeOnLan, String> {
+    let conn = Connection::system().await.map_err(|err| err.to_string())?;
+    BatteryHelperProxy::new(&conn)
+        .await
+        .map_err(|err| err.to_string())?
+        .set_wake_on_lan(&interface, enabled)
+        .await
+        .map_err(|err| format!("Failed to set Wake-on-LAN for {interface}: {err}"))?;
+    let enabled = read_wakeup(&interface).await.unwrap_or(enabled);
+    Ok(WakeOnLan { interface, enabled })
+}
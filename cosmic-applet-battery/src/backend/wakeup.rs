@@ -0,0 +1,158 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+
+// needs root, but reports every source in one read
+const DEBUGFS_WAKEUP_SOURCES: &str = "/sys/kernel/debug/wakeup_sources";
+const SYSFS_WAKEUP_PATH: &str = "/sys/class/wakeup";
+const USB_DEVICES_PATH: &str = "/sys/bus/usb/devices";
+
+/// Time a source may hold off suspend across one sleep before it's flagged.
+const DRAIN_THRESHOLD_MS: u64 = 5000;
+const REPORT_SOURCES: usize = 3;
+
+/// Counters of a kernel wakeup source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakeupSource {
+    pub name: String,
+    pub wakeup_count: u64,
+    pub prevent_suspend_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeKind {
+    Usb,
+    Network,
+    Timer,
+    Lid,
+    PowerButton,
+    Other,
+}
+
+/// A source that woke the system from the last sleep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeSource {
+    pub kind: WakeKind,
+    /// Product name where known, otherwise the kernel name
+    pub device: String,
+    /// Kept the system awake for much longer than a wakeup should take
+    pub drain: bool,
+}
+
+/// Parses the debugfs table, where columns are `name active_count
+/// event_count wakeup_count expire_count active_since total_time max_time
+/// last_change prevent_suspend_time`.
+fn parse_debugfs(contents: &str) -> Vec<WakeupSource> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            Some(WakeupSource {
+                name: fields.first()?.to_string(),
+                wakeup_count: fields.get(3)?.parse().ok()?,
+                prevent_suspend_ms: fields.get(9)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+async fn read_sysfs_sources() -> Vec<WakeupSource> {
+    let mut sources = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(SYSFS_WAKEUP_PATH).await else {
+        return sources;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let read = |attr: &'static str| {
+            let path = path.join(attr);
+            async move {
+                tokio::fs::read_to_string(path)
+                    .await
+                    .ok()
+                    .map(|s| s.trim().to_string())
+            }
+        };
+        let Some(name) = read("name").await else {
+            continue;
+        };
+        sources.push(WakeupSource {
+            name,
+            wakeup_count: read("wakeup_count")
+                .await
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            prevent_suspend_ms: read("prevent_suspend_time_ms")
+                .await
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+        });
+    }
+    sources
+}
+
+pub async fn wakeup_sources() -> Vec<WakeupSource> {
+    match tokio::fs::read_to_string(DEBUGFS_WAKEUP_SOURCES).await {
+        Ok(contents) => parse_debugfs(&contents),
+        Err(_) => read_sysfs_sources().await,
+    }
+}
+
+async fn classify(name: &str) -> (WakeKind, String) {
+    let usb_product = Path::new(USB_DEVICES_PATH).join(name).join("product");
+    if let Ok(product) = tokio::fs::read_to_string(usb_product).await {
+        return (WakeKind::Usb, product.trim().to_string());
+    }
+    let kind = if name.starts_with("usb") {
+        WakeKind::Usb
+    } else if name.starts_with("rtc") || name.contains("alarmtimer") {
+        WakeKind::Timer
+    } else if ["phy", "wlan", "wlp", "eth", "en"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        WakeKind::Network
+    } else if name.starts_with("PNP0C0D") {
+        WakeKind::Lid
+    } else if name.starts_with("PNP0C0C") || name.starts_with("LNXPWRBN") {
+        WakeKind::PowerButton
+    } else {
+        WakeKind::Other
+    };
+    (kind, name.to_string())
+}
+
+/// Compares counters taken before and after a sleep, returning the sources
+/// that fired while asleep, most frequent first.
+pub async fn resume_report(before: &[WakeupSource], after: &[WakeupSource]) -> Vec<ResumeSource> {
+    let mut fired: Vec<(u64, u64, &str)> = after
+        .iter()
+        .filter_map(|source| {
+            let (wakeups, prevented) = before.iter().find(|b| b.name == source.name).map_or(
+                (source.wakeup_count, source.prevent_suspend_ms),
+                |b| {
+                    (
+                        source.wakeup_count.saturating_sub(b.wakeup_count),
+                        source
+                            .prevent_suspend_ms
+                            .saturating_sub(b.prevent_suspend_ms),
+                    )
+                },
+            );
+            (wakeups > 0).then_some((wakeups, prevented, source.name.as_str()))
+        })
+        .collect();
+    fired.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut report = Vec::new();
+    for (_, prevented, name) in fired.into_iter().take(REPORT_SOURCES) {
+        let (kind, device) = classify(name).await;
+        report.push(ResumeSource {
+            kind,
+            device,
+            drain: prevented > DRAIN_THRESHOLD_MS,
+        });
+    }
+    report
+}
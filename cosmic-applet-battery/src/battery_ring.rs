@@ -0,0 +1,292 @@
+//! A ring of dots around the battery icon that empties clockwise as the
+//! battery drains.
+//!
+//! The renderer only draws quads, so the arc is made of round segments rather
+//! than a stroked path. Changes in charge ease in over a short animation.
+
+use std::time::Duration;
+
+use cosmic::iced::Vector;
+use cosmic::iced_core::{
+    Border, Clipboard, Color, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget,
+    event::{self, Event},
+    layout, mouse, overlay, renderer,
+    time::Instant,
+    widget::{Operation, Tree, tree},
+    window,
+};
+
+const SIZE: f32 = 40.0;
+const SEGMENTS: usize = 40;
+const ANIMATION: Duration = Duration::from_millis(300);
+// charge at or below which the ring turns orange, then red
+const WARNING_PERCENT: f32 = 50.0;
+const CRITICAL_PERCENT: f32 = 20.0;
+
+/// Wraps `content`, usually an icon, in a ring showing `percent`.
+pub fn battery_ring<'a, Message>(
+    content: impl Into<Element<'a, Message, cosmic::Theme, cosmic::Renderer>>,
+    percent: f64,
+) -> BatteryRing<'a, Message> {
+    BatteryRing {
+        content: content.into(),
+        percent: percent.clamp(0.0, 100.0) as f32,
+    }
+}
+
+#[allow(missing_debug_implementations)]
+pub struct BatteryRing<'a, Message> {
+    content: Element<'a, Message, cosmic::Theme, cosmic::Renderer>,
+    percent: f32,
+}
+
+/// Local state of the [`BatteryRing`].
+struct State {
+    from: f32,
+    to: f32,
+    /// Driven by redraws, so the animation starts on the first frame after a change
+    start: Option<Instant>,
+    current: f32,
+}
+
+impl State {
+    fn animating(&self) -> bool {
+        self.current != self.to
+    }
+}
+
+fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+impl<Message> Widget<Message, cosmic::Theme, cosmic::Renderer> for BatteryRing<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State {
+            from: self.percent,
+            to: self.percent,
+            start: None,
+            current: self.percent,
+        })
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&mut self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<State>();
+        if state.to != self.percent {
+            state.from = state.current;
+            state.to = self.percent;
+            state.start = None;
+        }
+        tree.diff_children(std::slice::from_mut(&mut self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(SIZE), Length::Fixed(SIZE))
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &cosmic::Renderer,
+        _limits: &layout::Limits,
+    ) -> layout::Node {
+        let size = Size::new(SIZE, SIZE);
+        let content = self.content.as_widget().layout(
+            &mut tree.children[0],
+            renderer,
+            &layout::Limits::new(Size::ZERO, size),
+        );
+        let offset = Point::new(
+            (size.width - content.size().width) / 2.0,
+            (size.height - content.size().height) / 2.0,
+        );
+        layout::Node::with_children(size, vec![content.move_to(offset)])
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &cosmic::Renderer,
+        operation: &mut dyn Operation<()>,
+    ) {
+        if let Some(content) = layout.children().next() {
+            self.content
+                .as_widget()
+                .operate(&mut tree.children[0], content, renderer, operation);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &cosmic::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        match event {
+            Event::Window(window::Event::RedrawRequested(now)) if state.animating() => {
+                let start = *state.start.get_or_insert(now);
+                let t =
+                    (now.duration_since(start).as_secs_f32() / ANIMATION.as_secs_f32()).min(1.0);
+                state.current = state.from + (state.to - state.from) * ease_in_out(t);
+                if t < 1.0 {
+                    shell.request_redraw(window::RedrawRequest::NextFrame);
+                } else {
+                    state.current = state.to;
+                }
+            }
+            _ => {}
+        }
+
+        let Some(content) = layout.children().next() else {
+            return event::Status::Ignored;
+        };
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            content,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &cosmic::Renderer,
+    ) -> mouse::Interaction {
+        let Some(content) = layout.children().next() else {
+            return mouse::Interaction::default();
+        };
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            content,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut cosmic::Renderer,
+        theme: &cosmic::Theme,
+        renderer_style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        use cosmic::iced_core::Renderer as _;
+
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let cosmic = theme.cosmic();
+        let fill: Color = if state.current <= CRITICAL_PERCENT {
+            cosmic.destructive_color().into()
+        } else if state.current <= WARNING_PERCENT {
+            cosmic.warning_color().into()
+        } else {
+            cosmic.success_color().into()
+        };
+        let track = Color {
+            a: 0.2,
+            ..renderer_style.text_color
+        };
+
+        // outline
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border: Border {
+                    color: track,
+                    width: 1.0,
+                    radius: (bounds.width / 2.0).into(),
+                },
+                ..Default::default()
+            },
+            Color::TRANSPARENT,
+        );
+
+        let dot = bounds.width / 12.0;
+        let radius = bounds.width / 2.0 - dot;
+        let center = bounds.center();
+        let filled = state.current / 100.0;
+        for i in 0..SEGMENTS {
+            let fraction = i as f32 / SEGMENTS as f32;
+            // clockwise from the top, y grows downwards
+            let angle = fraction * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
+            let position = Point::new(
+                center.x + radius * angle.cos() - dot / 2.0,
+                center.y + radius * angle.sin() - dot / 2.0,
+            );
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle::new(position, Size::new(dot, dot)),
+                    border: Border {
+                        radius: (dot / 2.0).into(),
+                        ..Border::default()
+                    },
+                    ..Default::default()
+                },
+                if fraction < filled { fill } else { track },
+            );
+        }
+
+        if let Some(content) = layout.children().next() {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                renderer_style,
+                content,
+                cursor,
+                viewport,
+            );
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &cosmic::Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, cosmic::Theme, cosmic::Renderer>> {
+        let content = layout.children().next()?;
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], content, renderer, translation)
+    }
+}
+
+impl<'a, Message: 'a> From<BatteryRing<'a, Message>>
+    for Element<'a, Message, cosmic::Theme, cosmic::Renderer>
+{
+    fn from(ring: BatteryRing<'a, Message>) -> Self {
+        Element::new(ring)
+    }
+}
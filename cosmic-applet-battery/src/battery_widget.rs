@@ -0,0 +1,50 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A battery level bar for embedding in popups, e.g. for peripherals.
+
+use cosmic::{
+    Element,
+    iced::{Alignment, Length, widget::progress_bar},
+    widget::{icon, row, text},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum BatteryWidgetMsg {
+    SetPercent(f64),
+    SetCharging(bool),
+}
+
+/// A horizontal bar with the percentage and, while charging, a bolt.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatteryWidget {
+    pub percent: f64,
+    pub charging: bool,
+}
+
+impl BatteryWidget {
+    pub fn update(&mut self, message: BatteryWidgetMsg) {
+        match message {
+            BatteryWidgetMsg::SetPercent(percent) => self.percent = percent.clamp(0.0, 100.0),
+            BatteryWidgetMsg::SetCharging(charging) => self.charging = charging,
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&self) -> Element<'a, Message> {
+        row()
+            .push(
+                progress_bar(0.0..=100.0, self.percent as f32)
+                    .height(Length::Fixed(6.0))
+                    .width(Length::Fill),
+            )
+            .push_maybe(self.charging.then(|| {
+                icon::from_name("power-profile-performance-symbolic")
+                    .size(14)
+                    .symbolic(true)
+            }))
+            .push(text::caption(format!("{:.0}%", self.percent)))
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into()
+    }
+}
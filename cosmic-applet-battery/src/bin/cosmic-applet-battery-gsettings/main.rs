@@ -0,0 +1,32 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copies the battery applet's settings between its COSMIC config and the
+//! `com.system76.cosmic-applet-battery` GSettings schema.
+
+use cosmic_applet_battery::gsettings::{Direction, convert};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: cosmic-applet-battery-gsettings <import|export>
+
+  import  copy GSettings values into the COSMIC config
+  export  copy the COSMIC config into GSettings";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let direction = match std::env::args().nth(1).as_deref() {
+        Some("import") => Direction::Import,
+        Some("export") => Direction::Export,
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match convert(direction).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(why) => {
+            eprintln!("{why}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Privileged D-Bus service performing the sysfs writes requested by the
+//! battery applet, after checking each request against polkit.
+//!
+//! The kernel forgets charge thresholds on reboot, so the last ones set are
+//! saved, and `--restore` applies them again at boot.
+
+use std::path::{Path, PathBuf};
+use zbus::{Connection, fdo, interface, message::Header};
+
+mod polkit;
+
+const NAME: &str = "com.system76.CosmicAppletBatteryHelper";
+const PATH: &str = "/com/system76/CosmicAppletBatteryHelper";
+
+const NET_PATH: &str = "/sys/class/net";
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+const MEM_SLEEP_PATH: &str = "/sys/power/mem_sleep";
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+const RAPL_PATH: &str = "/sys/class/powercap/intel-rapl:0";
+const USB_DEVICES_PATH: &str = "/sys/bus/usb/devices";
+const SAVED_THRESHOLDS_PATH: &str = "/var/lib/cosmic-applet-battery/charge-thresholds";
+
+struct Helper;
+
+#[interface(name = "com.system76.CosmicAppletBatteryHelper")]
+impl Helper {
+    async fn set_mem_sleep(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        state: &str,
+    ) -> fdo::Result<()> {
+        if !matches!(state, "s2idle" | "shallow" | "deep") {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Unknown mem_sleep state {state}"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-mem-sleep").await?;
+        write(MEM_SLEEP_PATH, state).await
+    }
+
+    async fn set_charge_thresholds(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        start: u8,
+        end: u8,
+    ) -> fdo::Result<()> {
+        if start >= end || end > 100 {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Invalid charge thresholds {start}-{end}"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-charging-limit").await?;
+        apply_charge_thresholds(start, end).await?;
+
+        let saved = Path::new(SAVED_THRESHOLDS_PATH);
+        if let Some(dir) = saved.parent() {
+            let _ = tokio::fs::create_dir_all(dir).await;
+        }
+        if let Err(err) = tokio::fs::write(saved, format!("{start} {end}\n")).await {
+            tracing::warn!("Failed to save the charge thresholds: {err}");
+        }
+        Ok(())
+    }
+
+    async fn set_cpu_governor(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        governor: &str,
+    ) -> fdo::Result<()> {
+        let cpu0 = Path::new(CPU_PATH).join("cpu0/cpufreq");
+        let available = tokio::fs::read_to_string(cpu0.join("scaling_available_governors"))
+            .await
+            .unwrap_or_default();
+        if !available.split_whitespace().any(|g| g == governor) {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Unknown CPU governor {governor}"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-cpu-governor").await?;
+
+        let Ok(mut entries) = tokio::fs::read_dir(CPU_PATH).await else {
+            return Err(fdo::Error::NotSupported(
+                "CPU frequency scaling is unavailable".to_string(),
+            ));
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path().join("cpufreq/scaling_governor");
+            if path.exists() {
+                write(&path, governor).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_usb_power_control(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        device: &str,
+        control: &str,
+    ) -> fdo::Result<()> {
+        let valid_device = is_entry_name(device)
+            && device
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '-' || c == '.');
+        if !valid_device || !matches!(control, "on" | "auto") {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Invalid USB power control {device}: {control}"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-usb-power").await?;
+        write(
+            Path::new(USB_DEVICES_PATH)
+                .join(device)
+                .join("power/control"),
+            control,
+        )
+        .await
+    }
+
+    async fn set_wake_on_lan(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        interface: &str,
+        enabled: bool,
+    ) -> fdo::Result<()> {
+        let wakeup = Path::new(NET_PATH)
+            .join(interface)
+            .join("device/power/wakeup");
+        // also keeps the name from being taken as an ethtool option
+        let valid = is_entry_name(interface) && !interface.starts_with('-');
+        if !valid || !wakeup.exists() {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Unknown network interface {interface}"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-wake-on-lan").await?;
+        write(&wakeup, if enabled { "enabled" } else { "disabled" }).await?;
+
+        // magic packet wakeups, or none
+        let status = tokio::process::Command::new("ethtool")
+            .args(["-s", interface, "wol", if enabled { "g" } else { "d" }])
+            .status()
+            .await
+            .map_err(|err| fdo::Error::Failed(format!("Failed to run ethtool: {err}")))?;
+        if !status.success() {
+            return Err(fdo::Error::Failed(format!("ethtool exited with {status}")));
+        }
+        Ok(())
+    }
+
+    async fn set_cpu_tdp(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+        microwatts: u64,
+    ) -> fdo::Result<()> {
+        let rapl = Path::new(RAPL_PATH);
+        let max = read_u64(&rapl.join("constraint_0_max_power_uw"))
+            .await
+            .unwrap_or(u64::MAX);
+        if microwatts == 0 || microwatts > max {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Power limit {microwatts} µW is out of range"
+            )));
+        }
+        polkit::authorize(conn, &header, "org.cosmic.battery.set-cpu-tdp").await?;
+        write(
+            &rapl.join("constraint_0_power_limit_uw"),
+            &microwatts.to_string(),
+        )
+        .await
+    }
+}
+
+async fn apply_charge_thresholds(start: u8, end: u8) -> fdo::Result<()> {
+    let batteries = batteries().await;
+    if batteries.is_empty() {
+        return Err(fdo::Error::NotSupported(
+            "No battery supports charge thresholds".to_string(),
+        ));
+    }
+    for battery in batteries {
+        let start_path = battery.join("charge_control_start_threshold");
+        let end_path = battery.join("charge_control_end_threshold");
+        // the kernel rejects a start threshold above the current end threshold
+        let current_end = read_u64(&end_path).await.unwrap_or(100);
+        if u64::from(start) >= current_end {
+            write(&end_path, &end.to_string()).await?;
+            if start_path.exists() {
+                write(&start_path, &start.to_string()).await?;
+            }
+        } else {
+            if start_path.exists() {
+                write(&start_path, &start.to_string()).await?;
+            }
+            write(&end_path, &end.to_string()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies the saved charge thresholds, if any were saved.
+async fn restore_charge_thresholds() -> fdo::Result<()> {
+    let Ok(saved) = tokio::fs::read_to_string(SAVED_THRESHOLDS_PATH).await else {
+        return Ok(());
+    };
+    // <start> <end>
+    let mut values = saved.split_whitespace().map(str::parse::<u8>);
+    match (values.next(), values.next()) {
+        (Some(Ok(start)), Some(Ok(end))) if start < end && end <= 100 => {
+            apply_charge_thresholds(start, end).await
+        }
+        _ => Err(fdo::Error::InvalidArgs(format!(
+            "Invalid saved charge thresholds in {SAVED_THRESHOLDS_PATH}"
+        ))),
+    }
+}
+
+async fn batteries() -> Vec<PathBuf> {
+    let mut batteries = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(POWER_SUPPLY_PATH).await else {
+        return batteries;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.join("charge_control_end_threshold").exists() {
+            batteries.push(path);
+        }
+    }
+    batteries
+}
+
+/// Whether `name` names an entry of a directory, rather than the directory
+/// itself, its parent or a deeper path.
+fn is_entry_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains('/')
+}
+
+async fn read_u64(path: &Path) -> Option<u64> {
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+async fn write(path: impl AsRef<Path>, value: &str) -> fdo::Result<()> {
+    let path = path.as_ref();
+    tracing::info!("Writing {value} to {}", path.display());
+    tokio::fs::write(path, value)
+        .await
+        .map_err(|err| fdo::Error::IOError(format!("{}: {err}", path.display())))
+}
+
+#[tokio::main]
+async fn main() -> zbus::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    if std::env::args().nth(1).as_deref() == Some("--restore") {
+        return restore_charge_thresholds().await.map_err(zbus::Error::from);
+    }
+
+    let _conn = zbus::connection::Builder::system()?
+        .name(NAME)?
+        .serve_at(PATH, Helper)?
+        .build()
+        .await?;
+
+    std::future::pending::<()>().await;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+use zbus::{Connection, fdo, message::Header, proxy, zvariant::Value};
+
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+#[proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+trait Authority {
+    /// CheckAuthorization method
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// Asks polkit whether the sender of the current message may perform `action_id`,
+/// prompting for credentials if the policy requires it.
+pub async fn authorize(conn: &Connection, header: &Header<'_>, action_id: &str) -> fdo::Result<()> {
+    let sender = header
+        .sender()
+        .ok_or_else(|| fdo::Error::AccessDenied("Message has no sender".to_string()))?;
+    let subject = (
+        "system-bus-name",
+        HashMap::from([("name", Value::from(sender.as_str()))]),
+    );
+
+    let authority = AuthorityProxy::new(conn).await?;
+    let (authorized, _, _) = authority
+        .check_authorization(
+            &subject,
+            action_id,
+            HashMap::new(),
+            ALLOW_USER_INTERACTION,
+            "",
+        )
+        .await?;
+
+    if authorized {
+        Ok(())
+    } else {
+        Err(fdo::Error::AccessDenied(format!(
+            "{sender} is not authorized for {action_id}"
+        )))
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Charging limit suggestions from the battery chemistry.
+//!
+//! The chemistry is looked up by matching the UPower model against the
+//! bundled `data/battery-chemistry.json`. Its entries are either whole model
+//! numbers or cell designation prefixes, such as `INR` in `INR18650-35E`.
+//! Batteries that match neither fall back on the UPower technology.
+
+use serde::Deserialize;
+
+const DATABASE: &str = include_str!("../data/battery-chemistry.json");
+// `org.freedesktop.UPower.Device.Technology` for lithium iron phosphate
+const TECHNOLOGY_LFP: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Chemistry {
+    Lfp,
+    Nmc,
+    Nca,
+}
+
+impl Chemistry {
+    /// Charge level balancing capacity against wear.
+    pub fn recommended_limit(self) -> u8 {
+        match self {
+            Self::Lfp => 100,
+            Self::Nmc => 80,
+            Self::Nca => 90,
+        }
+    }
+
+    /// Charge thresholds for the recommended limit, `None` if the battery is
+    /// best charged fully.
+    pub fn thresholds(self) -> Option<(u8, u8)> {
+        let end = self.recommended_limit();
+        (end < 100).then(|| (end - 10, end))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Pattern {
+    /// The whole model string
+    Model(String),
+    /// Cell designation prefix, followed by the digits of the cell size
+    Prefix(String),
+}
+
+impl Pattern {
+    fn matches(&self, model: &str) -> bool {
+        match self {
+            Self::Model(name) => model.trim().eq_ignore_ascii_case(name),
+            Self::Prefix(prefix) => model
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|code| {
+                    code.get(..prefix.len())
+                        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+                        && code[prefix.len()..].starts_with(|c: char| c.is_ascii_digit())
+                }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(flatten)]
+    pattern: Pattern,
+    chemistry: Chemistry,
+}
+
+pub fn detect(model: &str, technology: u32) -> Option<Chemistry> {
+    let entries: Vec<Entry> = serde_json::from_str(DATABASE)
+        .inspect_err(|err| tracing::error!("Invalid battery chemistry database: {err}"))
+        .unwrap_or_default();
+    entries
+        .into_iter()
+        .find(|entry| entry.pattern.matches(model))
+        .map(|entry| entry.chemistry)
+        .or((technology == TECHNOLOGY_LFP).then_some(Chemistry::Lfp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_designations() {
+        assert_eq!(detect("INR18650-35E", 0), Some(Chemistry::Nmc));
+        assert_eq!(detect("Samsung inr18650", 0), Some(Chemistry::Nmc));
+        assert_eq!(detect("NCR18650B", 0), Some(Chemistry::Nca));
+        assert_eq!(detect("IFR26650", 0), Some(Chemistry::Lfp));
+    }
+
+    #[test]
+    fn whole_model_numbers() {
+        let pattern = Pattern::Model("5B10W13930".to_string());
+        assert!(pattern.matches("5b10w13930 "));
+        assert!(!pattern.matches("5B10W13930X"));
+        assert!(!pattern.matches("L5B10W13930"));
+    }
+
+    #[test]
+    fn no_substring_matches() {
+        // the prefix must start a code and be followed by the cell size
+        assert_eq!(detect("FINR18650", 0), None);
+        assert_eq!(detect("INR", 0), None);
+        assert_eq!(detect("INRA1", 0), None);
+        assert_eq!(detect("5B10W13930", 0), None);
+        assert_eq!(detect("", 0), None);
+    }
+
+    #[test]
+    fn technology_fallback() {
+        assert_eq!(detect("5B10W13930", TECHNOLOGY_LFP), Some(Chemistry::Lfp));
+        assert_eq!(detect("INR18650", TECHNOLOGY_LFP), Some(Chemistry::Nmc));
+    }
+}
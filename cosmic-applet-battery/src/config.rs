@@ -1,4 +1,399 @@
 // Copyright 2023 System76 <info@system76.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Action run by a swipe across the panel icon.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SwipeAction {
+    #[default]
+    None,
+    TogglePowerSaver,
+    CycleBrightness,
+    Suspend,
+}
+
+/// What the mouse wheel over the panel icon adjusts.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScrollAction {
+    #[default]
+    None,
+    ScreenBrightness,
+}
+
+/// Where the battery icon is shown.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TrayMode {
+    /// The applet's own panel button
+    #[default]
+    Panel,
+    /// A StatusNotifierItem, for system trays
+    StatusNotifierItem,
+}
+
+/// What the panel button shows of the battery.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PanelDisplay {
+    #[default]
+    Icon,
+    IconAndPercentage,
+    Percentage,
+}
+
+impl PanelDisplay {
+    /// Name used in GSettings
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Icon => "icon",
+            Self::IconAndPercentage => "icon-and-percentage",
+            Self::Percentage => "percentage",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "icon" => Some(Self::Icon),
+            "icon-and-percentage" => Some(Self::IconAndPercentage),
+            "percentage" => Some(Self::Percentage),
+            _ => None,
+        }
+    }
+}
+
+/// State of the charging limit suggestion based on the battery chemistry.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ChemistrySuggestion {
+    #[default]
+    Pending,
+    Accepted,
+    Dismissed,
+}
+
+/// Average power draw measured in a power profile.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProfilePower {
+    pub milliwatts: u32,
+    pub measurements: u32,
+}
+
+impl ProfilePower {
+    // caps the weight of old measurements, so the average follows changes in usage
+    const MAX_WEIGHT: u32 = 10;
+
+    pub fn add(&mut self, milliwatts: u32) {
+        let weight = self.measurements.min(Self::MAX_WEIGHT);
+        self.milliwatts = ((u64::from(self.milliwatts) * u64::from(weight) + u64::from(milliwatts))
+            / u64::from(weight + 1)) as u32;
+        self.measurements += 1;
+    }
+}
+
+/// Step of a running battery calibration.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CalibrationStep {
+    /// Running down to empty on battery power
+    Discharge { restore_limit: bool },
+    /// Charging back up to full
+    Charge { restore_limit: bool },
+}
+
 pub const APP_ID: &str = "com.system76.CosmicAppletButton";
+
+#[derive(Debug, Clone, Serialize, Deserialize, CosmicConfigEntry, PartialEq)]
+#[version = 1]
+pub struct BatteryConfig {
+    /// Charge thresholds to restore once storage mode is disabled.
+    /// Storage mode is active while this is set.
+    pub storage_mode_restore: Option<(u8, u8)>,
+    /// Apply the charging limit while docked on AC power.
+    pub docked_battery_protection: bool,
+    /// Set while the charging limit was applied by docked battery protection,
+    /// so it's only lifted again if the applet enabled it.
+    pub docked_limit_applied: bool,
+    /// Notify with the remaining charge when AC power is disconnected.
+    pub notify_on_unplug: bool,
+    /// Notify with the current charge when AC power is connected.
+    pub notify_on_plug_in: bool,
+    /// Times at which the system powers off, after a countdown.
+    pub shutdown_schedule: Vec<(Weekday, NaiveTime)>,
+    /// Show low battery alerts in the popover only, without notifications.
+    pub silent_notifications: bool,
+    /// Icon theme to take battery icons from instead of the applet's own.
+    pub battery_icon_theme: Option<String>,
+    /// Power profile to switch to when AC power is connected, one of
+    /// `battery`, `balanced` or `performance`. `None` leaves it alone.
+    pub profile_on_ac: Option<String>,
+    /// Power profile to switch to when running on battery.
+    pub profile_on_battery: Option<String>,
+    pub swipe_left: SwipeAction,
+    pub swipe_right: SwipeAction,
+    pub swipe_down: SwipeAction,
+    /// Condense the popup to the essentials when the battery is low.
+    pub low_power_density_ui: bool,
+    /// Show the battery icon in a system tray instead of the panel.
+    pub tray_mode: TrayMode,
+    /// Answer to the one-time charging limit suggestion for the battery chemistry.
+    pub chemistry_suggestion: ChemistrySuggestion,
+    /// Average power draw measured on battery after switching to each power
+    /// profile, keyed by profile name.
+    pub profile_power: BTreeMap<String, ProfilePower>,
+    /// When a calibration run last finished. Batteries don't report this, so
+    /// it's unset until the battery is calibrated from the applet.
+    pub last_calibration: Option<NaiveDate>,
+    /// The running calibration, so it continues across restarts.
+    pub calibration: Option<CalibrationStep>,
+    /// When the calibration recommendation was last dismissed.
+    pub calibration_dismissed: Option<NaiveDate>,
+    /// CPU temperature in °C that, held for a while, switches to the power
+    /// saver profile until it cools down.
+    pub thermal_mitigation_threshold: u32,
+    /// Keep the `com.system76.cosmic-applet-battery` GSettings schema in sync
+    /// with this config.
+    pub gsettings_sync: bool,
+    /// Dim the screen and then suspend on battery power when input has
+    /// been rare enough that a long break seems likely.
+    pub smart_sleep: bool,
+    /// Thunderbolt `unique_id` of the dock that switches the power profile
+    /// and brightness while connected.
+    pub dock_uuid: Option<String>,
+    /// Power profile while docked, performance if unset.
+    pub dock_profile: Option<String>,
+    /// Screen brightness while docked in percent, 100 if unset.
+    pub dock_brightness: Option<u32>,
+    /// Watt-hours the battery may supply per day, or 0 for no budget. Only
+    /// used with the `energy-goals` feature.
+    pub daily_energy_budget: u32,
+    /// Percent of the screen brightness range one mouse wheel step over the
+    /// panel icon changes.
+    pub scroll_brightness_step: u32,
+    /// Last screen brightness the settings daemon reported, with its maximum,
+    /// shown until it reports again.
+    pub screen_brightness_fallback: Option<(i32, i32)>,
+    /// Last keyboard backlight brightness UPower reported, with its maximum.
+    pub kbd_brightness_fallback: Option<(i32, i32)>,
+    /// Charge level the charging limit stops at, in percent. Charging resumes
+    /// 10% below it.
+    pub charge_limit_threshold: u8,
+    /// Charge in percent at which the low battery warning starts.
+    pub low_battery_percent: u32,
+    /// Charge in percent at which the battery counts as critical.
+    pub critical_battery_percent: u32,
+    /// Suspend once the battery is critical while still on battery power.
+    pub suspend_on_critical: bool,
+    /// What the panel button shows.
+    pub panel_display: PanelDisplay,
+    /// Charge in percent below which, on battery power, the power saver profile
+    /// is switched to until AC power is back. 0 never switches.
+    pub power_saver_percent: u32,
+    /// Screen brightness in percent to dim to along with it, if not already
+    /// dimmer. `None` leaves the brightness alone.
+    pub power_saver_brightness: Option<u32>,
+    /// What scrolling over the panel icon adjusts.
+    pub scroll_action: ScrollAction,
+    /// Battery health in percent of the design capacity below which it is shown
+    /// as a warning.
+    pub low_health_percent: u32,
+    /// Move the brightness of external displays together with the built-in one.
+    pub link_displays: bool,
+    /// Tints the screen warmer.
+    pub night_light: bool,
+    /// Colour temperature of the night light, in kelvin.
+    pub night_light_temperature: u32,
+    /// Turns the night light on from sunset to sunrise.
+    pub night_light_automatic: bool,
+    /// Latitude and longitude in degrees for the sunset and sunrise of the
+    /// automatic night light, which falls back to fixed hours without one.
+    pub night_light_location: Option<(f64, f64)>,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        Self {
+            storage_mode_restore: None,
+            docked_battery_protection: false,
+            docked_limit_applied: false,
+            notify_on_unplug: true,
+            notify_on_plug_in: true,
+            shutdown_schedule: Vec::new(),
+            silent_notifications: false,
+            battery_icon_theme: None,
+            profile_on_ac: None,
+            profile_on_battery: None,
+            swipe_left: SwipeAction::TogglePowerSaver,
+            swipe_right: SwipeAction::CycleBrightness,
+            swipe_down: SwipeAction::Suspend,
+            low_power_density_ui: true,
+            tray_mode: TrayMode::Panel,
+            chemistry_suggestion: ChemistrySuggestion::Pending,
+            profile_power: BTreeMap::new(),
+            last_calibration: None,
+            calibration: None,
+            calibration_dismissed: None,
+            thermal_mitigation_threshold: 90,
+            gsettings_sync: false,
+            smart_sleep: false,
+            dock_uuid: None,
+            dock_profile: None,
+            dock_brightness: None,
+            daily_energy_budget: 0,
+            scroll_brightness_step: 5,
+            screen_brightness_fallback: None,
+            kbd_brightness_fallback: None,
+            charge_limit_threshold: 80,
+            low_battery_percent: 20,
+            critical_battery_percent: 5,
+            suspend_on_critical: false,
+            panel_display: PanelDisplay::Icon,
+            power_saver_percent: 0,
+            power_saver_brightness: None,
+            scroll_action: ScrollAction::ScreenBrightness,
+            low_health_percent: 60,
+            link_displays: false,
+            night_light: false,
+            night_light_temperature: 4000,
+            night_light_automatic: false,
+            night_light_location: None,
+        }
+    }
+}
+
+/// Fields that differ between two configs, as `(field, old, new)`.
+#[derive(Debug, Default)]
+pub struct BatteryConfigDiff(Vec<(&'static str, String, String)>);
+
+impl BatteryConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Display for BatteryConfigDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (field, old, new)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{field} {old} → {new}")?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! diff_fields {
+    ($old:expr, $new:expr, $($field:ident),+ $(,)?) => {{
+        let mut diff = BatteryConfigDiff::default();
+        $(
+            if $old.$field != $new.$field {
+                diff.0.push((
+                    stringify!($field),
+                    format!("{:?}", $old.$field),
+                    format!("{:?}", $new.$field),
+                ));
+            }
+        )+
+        diff
+    }};
+}
+
+impl BatteryConfig {
+    pub fn diff(&self, new: &Self) -> BatteryConfigDiff {
+        // destructure so a new field can't be left out of the diff
+        let Self {
+            storage_mode_restore: _,
+            docked_battery_protection: _,
+            docked_limit_applied: _,
+            notify_on_unplug: _,
+            notify_on_plug_in: _,
+            shutdown_schedule: _,
+            silent_notifications: _,
+            battery_icon_theme: _,
+            profile_on_ac: _,
+            profile_on_battery: _,
+            swipe_left: _,
+            swipe_right: _,
+            swipe_down: _,
+            low_power_density_ui: _,
+            tray_mode: _,
+            chemistry_suggestion: _,
+            profile_power: _,
+            last_calibration: _,
+            calibration: _,
+            calibration_dismissed: _,
+            thermal_mitigation_threshold: _,
+            gsettings_sync: _,
+            smart_sleep: _,
+            dock_uuid: _,
+            dock_profile: _,
+            dock_brightness: _,
+            daily_energy_budget: _,
+            scroll_brightness_step: _,
+            screen_brightness_fallback: _,
+            kbd_brightness_fallback: _,
+            charge_limit_threshold: _,
+            low_battery_percent: _,
+            critical_battery_percent: _,
+            suspend_on_critical: _,
+            panel_display: _,
+            power_saver_percent: _,
+            power_saver_brightness: _,
+            scroll_action: _,
+            low_health_percent: _,
+            link_displays: _,
+            night_light: _,
+            night_light_temperature: _,
+            night_light_automatic: _,
+            night_light_location: _,
+        } = self;
+        diff_fields!(
+            self,
+            new,
+            storage_mode_restore,
+            docked_battery_protection,
+            docked_limit_applied,
+            notify_on_unplug,
+            notify_on_plug_in,
+            shutdown_schedule,
+            silent_notifications,
+            battery_icon_theme,
+            profile_on_ac,
+            profile_on_battery,
+            swipe_left,
+            swipe_right,
+            swipe_down,
+            low_power_density_ui,
+            tray_mode,
+            chemistry_suggestion,
+            profile_power,
+            last_calibration,
+            calibration,
+            calibration_dismissed,
+            thermal_mitigation_threshold,
+            gsettings_sync,
+            smart_sleep,
+            dock_uuid,
+            dock_profile,
+            dock_brightness,
+            daily_energy_budget,
+            scroll_brightness_step,
+            screen_brightness_fallback,
+            kbd_brightness_fallback,
+            charge_limit_threshold,
+            low_battery_percent,
+            critical_battery_percent,
+            suspend_on_critical,
+            panel_display,
+            power_saver_percent,
+            power_saver_brightness,
+            scroll_action,
+            low_health_percent,
+            link_displays,
+            night_light,
+            night_light_temperature,
+            night_light_automatic,
+            night_light_location,
+        )
+    }
+}
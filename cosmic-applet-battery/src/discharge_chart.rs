@@ -0,0 +1,83 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A bar chart of the recent charge history, continued by a dashed projection
+//! of the discharge at a hypothetical power draw.
+
+use crate::history::History;
+use chrono::{Local, TimeDelta};
+use cosmic::{
+    Element,
+    iced::{
+        Alignment, Background, Border, Color, Length, Shadow,
+        widget::{Row, column, container, vertical_space},
+    },
+};
+
+const COLUMNS: i32 = 48;
+const COLUMN_MINUTES: i64 = 10;
+const HEIGHT: f32 = 60.0;
+
+/// Remaining time at `watts` from `energy` watt-hours.
+pub fn projected_time(energy: f64, watts: f64) -> Option<std::time::Duration> {
+    (watts > 0.0 && energy > 0.0)
+        .then(|| std::time::Duration::from_secs_f64(energy / watts * 3600.0))
+}
+
+fn bar<'a, Message: 'a>(percent: f64, projected: bool) -> Element<'a, Message> {
+    let height = (percent.clamp(0.0, 100.0) / 100.0) as f32 * HEIGHT;
+    let fill = container(vertical_space().height(Length::Fixed(height)))
+        .width(Length::Fixed(3.0))
+        .class(cosmic::style::Container::Custom(Box::new(move |theme| {
+            let mut color = theme.cosmic().accent_color();
+            if projected {
+                color.alpha *= 0.5;
+            }
+            container::Style {
+                text_color: None,
+                background: Some(Background::Color(color.into())),
+                border: Border {
+                    radius: 1.0.into(),
+                    width: 0.0,
+                    color: Color::TRANSPARENT,
+                },
+                shadow: Shadow::default(),
+                icon_color: None,
+            }
+        })));
+    column![vertical_space().height(Length::Fill), fill]
+        .height(Length::Fixed(HEIGHT))
+        .into()
+}
+
+/// The left half shows history, the right half the projection from the
+/// current `percent`, leaving every other column empty so it reads as dashed.
+pub fn view<'a, Message: 'a>(
+    history: &History,
+    percent: f64,
+    energy: f64,
+    watts: f64,
+) -> Element<'a, Message> {
+    let now = Local::now();
+    let hours_left = projected_time(energy, watts).map(|time| time.as_secs_f64() / 3600.0);
+    let half = COLUMNS / 2;
+    let bars = (-half..half).map(|i| {
+        let offset = TimeDelta::minutes(i64::from(i) * COLUMN_MINUTES);
+        if i <= 0 {
+            return match history.percent_at(now + offset) {
+                Some(percent) => bar(percent, false),
+                None => bar(0.0, false),
+            };
+        }
+        if i % 2 == 1 {
+            return bar(0.0, true);
+        }
+        let hours = offset.num_minutes() as f64 / 60.0;
+        let projected = hours_left.map_or(percent, |left| percent * (1.0 - hours / left));
+        bar(projected, true)
+    });
+    Row::with_children(bars)
+        .spacing(1)
+        .align_y(Alignment::End)
+        .into()
+}
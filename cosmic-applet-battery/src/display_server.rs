@@ -0,0 +1,39 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Detects the display server, since the panel integration needs Wayland.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayServer {
+    X11,
+    Wayland,
+    #[default]
+    Unknown,
+}
+
+impl DisplayServer {
+    /// Checks `WAYLAND_DISPLAY` before `DISPLAY`, as XWayland sets both.
+    pub fn detect() -> Self {
+        let set = |var| std::env::var_os(var).is_some_and(|value| !value.is_empty());
+        if set("WAYLAND_DISPLAY") {
+            Self::Wayland
+        } else if set("DISPLAY") {
+            Self::X11
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::X11 => "X11",
+            Self::Wayland => "Wayland",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// The panel button and its layer shell popup.
+    pub fn supports_panel(self) -> bool {
+        self == Self::Wayland
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A daily budget for the energy drawn from the battery.
+//!
+//! Experimental, so only built with the `energy-goals` feature. Usage is
+//! counted from the session energy totals, starting over at midnight.
+
+use chrono::NaiveDate;
+
+// share of the budget at which to warn
+const WARN_FRACTION: f64 = 0.8;
+
+pub const EXCEEDED_ICON: &str = "cosmic-applet-battery-budget-exceeded-symbolic";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnergyGoal {
+    day: Option<NaiveDate>,
+    /// Session total when the day started
+    start: f64,
+    /// Watt-hours drawn today
+    pub used: f64,
+    warned: bool,
+}
+
+impl EnergyGoal {
+    /// Updates today's usage from the session total `used`. Returns `true`
+    /// the first time it passes 80% of `budget` on a given day.
+    pub fn update(&mut self, today: NaiveDate, used: f64, budget: f64) -> bool {
+        if self.day != Some(today) {
+            *self = Self {
+                day: Some(today),
+                start: used,
+                ..Self::default()
+            };
+        }
+        self.used = used - self.start;
+        if budget <= 0.0 || self.warned || self.used < budget * WARN_FRACTION {
+            return false;
+        }
+        self.warned = true;
+        true
+    }
+
+    pub fn exceeded(&self, budget: f64) -> bool {
+        budget > 0.0 && self.used > budget
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::fl;
+use std::time::Duration;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// Describes a duration approximately, such as "about 2 hours", in the active
+/// locale.
+pub fn duration_to_natural(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 5 * MINUTE {
+        fl!("time-less-than-5-minutes")
+    } else if secs < 50 * MINUTE {
+        // nearest 5 minutes
+        let minutes = (secs + 150) / 300 * 5;
+        fl!("time-about-minutes", minutes = minutes)
+    } else if secs < 90 * MINUTE {
+        fl!("time-about-an-hour")
+    } else if secs < 20 * HOUR {
+        let hours = (secs + HOUR / 2) / HOUR;
+        fl!("time-about-hours", hours = hours)
+    } else if secs < 30 * HOUR {
+        fl!("time-about-a-day")
+    } else if secs < 42 * HOUR {
+        fl!("time-about-a-day-and-a-half")
+    } else {
+        let days = (secs + DAY / 2) / DAY;
+        fl!("time-about-days", days = days)
+    }
+}
+
+/// Formats a duration to the minute, such as "2 hr 34 min", in the active
+/// locale.
+pub fn duration_to_short(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < MINUTE {
+        return fl!("time-less-than-a-minute");
+    }
+    let hours = secs / HOUR;
+    let minutes = secs % HOUR / MINUTE;
+    match (hours, minutes) {
+        (0, minutes) => fl!("time-minutes", minutes = minutes),
+        (hours, 0) => fl!("time-hours", hours = hours),
+        (hours, minutes) => fl!("time-hours-minutes", hours = hours, minutes = minutes),
+    }
+}
+
+/// Describes the time until the battery is full, or empty, such as
+/// "2 hr 34 min until full". UPower reports 0 while it has no estimate yet.
+pub fn time_remaining(duration: Duration, charging: bool) -> String {
+    if duration.is_zero() {
+        return fl!("time-estimating");
+    }
+    let time = duration_to_short(duration);
+    if charging {
+        fl!("time-until-full", time = time)
+    } else {
+        fl!("time-until-empty", time = time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn natural_bands() {
+        let cases = [
+            (secs(0), fl!("time-less-than-5-minutes")),
+            (secs(59), fl!("time-less-than-5-minutes")),
+            (secs(5 * MINUTE - 1), fl!("time-less-than-5-minutes")),
+            (secs(5 * MINUTE), fl!("time-about-minutes", minutes = 5)),
+            (
+                secs(50 * MINUTE - 1),
+                fl!("time-about-minutes", minutes = 50),
+            ),
+            (secs(50 * MINUTE), fl!("time-about-an-hour")),
+            (secs(90 * MINUTE - 1), fl!("time-about-an-hour")),
+            (secs(90 * MINUTE), fl!("time-about-hours", hours = 2)),
+            (secs(20 * HOUR - 1), fl!("time-about-hours", hours = 20)),
+            (secs(20 * HOUR), fl!("time-about-a-day")),
+            (secs(30 * HOUR - 1), fl!("time-about-a-day")),
+            (secs(30 * HOUR), fl!("time-about-a-day-and-a-half")),
+            (secs(42 * HOUR - 1), fl!("time-about-a-day-and-a-half")),
+            (secs(42 * HOUR), fl!("time-about-days", days = 2)),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(duration_to_natural(duration), expected, "{duration:?}");
+        }
+    }
+
+    #[test]
+    fn short_bands() {
+        let cases = [
+            (secs(0), fl!("time-less-than-a-minute")),
+            (secs(59), fl!("time-less-than-a-minute")),
+            (secs(MINUTE), fl!("time-minutes", minutes = 1)),
+            (secs(5 * MINUTE), fl!("time-minutes", minutes = 5)),
+            (secs(50 * MINUTE), fl!("time-minutes", minutes = 50)),
+            (
+                secs(90 * MINUTE),
+                fl!("time-hours-minutes", hours = 1, minutes = 30),
+            ),
+            (secs(20 * HOUR), fl!("time-hours", hours = 20)),
+            (secs(30 * HOUR), fl!("time-hours", hours = 30)),
+            (secs(42 * HOUR), fl!("time-hours", hours = 42)),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(duration_to_short(duration), expected, "{duration:?}");
+        }
+    }
+
+    #[test]
+    fn remaining_without_estimate() {
+        // UPower reports no estimate as 0
+        assert_eq!(time_remaining(Duration::ZERO, true), fl!("time-estimating"));
+        assert_eq!(
+            time_remaining(Duration::ZERO, false),
+            fl!("time-estimating")
+        );
+    }
+
+    #[test]
+    fn remaining_direction() {
+        let time = duration_to_short(secs(90 * MINUTE));
+        assert_eq!(
+            time_remaining(secs(90 * MINUTE), true),
+            fl!("time-until-full", time = time.clone())
+        );
+        assert_eq!(
+            time_remaining(secs(90 * MINUTE), false),
+            fl!("time-until-empty", time = time)
+        );
+    }
+}
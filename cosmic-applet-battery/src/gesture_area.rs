@@ -0,0 +1,340 @@
+//! A container for capturing touch swipes and mouse wheel scrolling.
+//!
+//! Touchpad gestures aren't forwarded to applets by the compositor, so this
+//! tracks touch points instead. On devices without a touchscreen no touch
+//! events are delivered and the handler simply never fires.
+
+use std::collections::HashMap;
+
+use cosmic::iced::Vector;
+use cosmic::iced_renderer::core::Point;
+
+use cosmic::iced_core::{
+    Clipboard, Element, Layout, Length, Rectangle, Shell, Size, Widget,
+    event::{self, Event},
+    layout, mouse, overlay, renderer, touch,
+    widget::{Operation, Tree, tree},
+};
+
+const SWIPE_FINGERS: usize = 3;
+// distance a single finger must travel to count as a swipe
+const SWIPE_THRESHOLD: f32 = 30.0;
+// touchpad scrolling per wheel step, in logical pixels
+const SCROLL_STEP: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Emit messages on touch swipes in any direction and on mouse wheel scrolling.
+#[allow(missing_debug_implementations)]
+pub struct GestureArea<'a, Message, Theme = cosmic::Theme, Renderer = cosmic::Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    on_vertical_swipe: Option<Box<dyn Fn(f32) -> Message + 'a>>,
+    on_swipe: Option<Box<dyn Fn(SwipeDirection) -> Message + 'a>>,
+    on_scroll: Option<Box<dyn Fn(i32) -> Message + 'a>>,
+}
+
+impl<'a, Message, Theme, Renderer> GestureArea<'a, Message, Theme, Renderer> {
+    /// Creates a [`GestureArea`] with the given content.
+    pub fn new(content: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        GestureArea {
+            content: content.into(),
+            on_vertical_swipe: None,
+            on_swipe: None,
+            on_scroll: None,
+        }
+    }
+
+    /// The message to emit as a three-finger swipe moves. The argument is the
+    /// vertical distance travelled since the last event, negative upwards, so
+    /// faster swipes produce larger steps.
+    #[must_use]
+    pub fn on_vertical_swipe(mut self, message: impl Fn(f32) -> Message + 'a) -> Self {
+        self.on_vertical_swipe = Some(Box::new(message));
+        self
+    }
+
+    /// The message to emit when a single finger swipes across the area.
+    #[must_use]
+    pub fn on_swipe(mut self, message: impl Fn(SwipeDirection) -> Message + 'a) -> Self {
+        self.on_swipe = Some(Box::new(message));
+        self
+    }
+
+    /// The message to emit when the mouse wheel scrolls over the area. The
+    /// argument is the number of wheel steps, positive upwards.
+    #[must_use]
+    pub fn on_scroll(mut self, message: impl Fn(i32) -> Message + 'a) -> Self {
+        self.on_scroll = Some(Box::new(message));
+        self
+    }
+}
+
+/// Local state of the [`GestureArea`].
+#[derive(Default)]
+struct State {
+    fingers: HashMap<touch::Finger, Point>,
+    last_centroid: Option<Point>,
+    /// Touchpad scrolling not yet making up a whole step
+    scrolled: f32,
+    /// Start of a single finger swipe, cleared if more fingers join
+    swipe_start: Option<(touch::Finger, Point)>,
+}
+
+impl State {
+    fn centroid(&self) -> Option<Point> {
+        if self.fingers.len() != SWIPE_FINGERS {
+            return None;
+        }
+        let (x, y) = self
+            .fingers
+            .values()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.x, y + p.y));
+        let n = self.fingers.len() as f32;
+        Some(Point::new(x / n, y / n))
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for GestureArea<'a, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&mut self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_mut(&mut self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<()>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        if let Some(on_swipe) = self.on_swipe.as_ref() {
+            match &event {
+                Event::Touch(touch::Event::FingerPressed { id, position })
+                    if layout.bounds().contains(*position) =>
+                {
+                    state.swipe_start = state.fingers.is_empty().then_some((*id, *position));
+                }
+                Event::Touch(touch::Event::FingerLifted { id, position }) => {
+                    if let Some((_, start)) = state.swipe_start.take_if(|(f, _)| f == id) {
+                        let (dx, dy) = (position.x - start.x, position.y - start.y);
+                        let direction = if dx.abs() >= dy.abs() {
+                            (dx.abs() >= SWIPE_THRESHOLD).then(|| {
+                                if dx < 0.0 {
+                                    SwipeDirection::Left
+                                } else {
+                                    SwipeDirection::Right
+                                }
+                            })
+                        } else {
+                            (dy.abs() >= SWIPE_THRESHOLD).then(|| {
+                                if dy < 0.0 {
+                                    SwipeDirection::Up
+                                } else {
+                                    SwipeDirection::Down
+                                }
+                            })
+                        };
+                        if let Some(direction) = direction {
+                            shell.publish((on_swipe)(direction));
+                        }
+                    }
+                }
+                Event::Touch(touch::Event::FingerLost { id, .. }) => {
+                    state.swipe_start.take_if(|(f, _)| f == id);
+                }
+                _ => {}
+            }
+        }
+        if let Some(on_scroll) = self.on_scroll.as_ref() {
+            match &event {
+                Event::Mouse(mouse::Event::WheelScrolled { delta })
+                    if cursor.is_over(layout.bounds()) =>
+                {
+                    state.scrolled += match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y * SCROLL_STEP,
+                        mouse::ScrollDelta::Pixels { y, .. } => *y,
+                    };
+                    let steps = (state.scrolled / SCROLL_STEP).trunc();
+                    state.scrolled -= steps * SCROLL_STEP;
+                    if steps != 0.0 {
+                        shell.publish((on_scroll)(steps as i32));
+                    }
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
+        if let Some(on_swipe) = self.on_vertical_swipe.as_ref() {
+            match &event {
+                Event::Touch(touch::Event::FingerPressed { id, position })
+                    if layout.bounds().contains(*position) =>
+                {
+                    state.fingers.insert(*id, *position);
+                    state.last_centroid = state.centroid();
+                    if state.fingers.len() > 1 {
+                        state.swipe_start = None;
+                    }
+                }
+                Event::Touch(touch::Event::FingerMoved { id, position }) => {
+                    if let Some(finger) = state.fingers.get_mut(id) {
+                        *finger = *position;
+                    }
+                    if let Some((last, centroid)) = state.last_centroid.zip(state.centroid()) {
+                        state.last_centroid = Some(centroid);
+                        shell.publish((on_swipe)(centroid.y - last.y));
+                        return event::Status::Captured;
+                    }
+                }
+                Event::Touch(
+                    touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. },
+                ) => {
+                    state.fingers.remove(id);
+                    state.last_centroid = state.centroid();
+                }
+                _ => {}
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        renderer_style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            renderer_style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+
+    fn drag_destinations(
+        &self,
+        state: &Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        dnd_rectangles: &mut cosmic::iced_core::clipboard::DndDestinationRectangles,
+    ) {
+        if let Some(state) = state.children.iter().next() {
+            self.content
+                .as_widget()
+                .drag_destinations(state, layout, renderer, dnd_rectangles);
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<GestureArea<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: 'a + renderer::Renderer,
+{
+    fn from(
+        area: GestureArea<'a, Message, Theme, Renderer>,
+    ) -> Element<'a, Message, Theme, Renderer> {
+        Element::new(area)
+    }
+}
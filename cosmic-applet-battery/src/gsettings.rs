@@ -0,0 +1,230 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Mirrors the user-facing part of [`BatteryConfig`] to the
+//! `com.system76.cosmic-applet-battery` GSettings schema, for GNOME tools.
+//!
+//! This goes through the `gsettings` command rather than linking GIO. The
+//! COSMIC config is authoritative: it's exported on startup, overwriting
+//! whatever GSettings held, and later GSettings changes are imported into it
+//! key by key.
+
+use crate::config::{BatteryConfig, PanelDisplay};
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use std::{fmt::Debug, hash::Hash, process::Stdio};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+};
+
+const SCHEMA: &str = "com.system76.cosmic-applet-battery";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// GSettings into the COSMIC config
+    Import,
+    /// The COSMIC config into GSettings
+    Export,
+}
+
+/// A GSettings key and its value in GVariant text format.
+fn keys(config: &BatteryConfig) -> [(&'static str, String); 13] {
+    let string = |value: &Option<String>| format!("'{}'", value.as_deref().unwrap_or_default());
+    [
+        (
+            "docked-battery-protection",
+            config.docked_battery_protection.to_string(),
+        ),
+        ("notify-on-unplug", config.notify_on_unplug.to_string()),
+        ("notify-on-plug-in", config.notify_on_plug_in.to_string()),
+        (
+            "silent-notifications",
+            config.silent_notifications.to_string(),
+        ),
+        (
+            "low-power-density-ui",
+            config.low_power_density_ui.to_string(),
+        ),
+        ("battery-icon-theme", string(&config.battery_icon_theme)),
+        ("profile-on-ac", string(&config.profile_on_ac)),
+        ("profile-on-battery", string(&config.profile_on_battery)),
+        (
+            "thermal-mitigation-threshold",
+            config.thermal_mitigation_threshold.to_string(),
+        ),
+        (
+            "low-battery-percent",
+            config.low_battery_percent.to_string(),
+        ),
+        (
+            "critical-battery-percent",
+            config.critical_battery_percent.to_string(),
+        ),
+        (
+            "suspend-on-critical",
+            config.suspend_on_critical.to_string(),
+        ),
+        (
+            "panel-display",
+            format!("'{}'", config.panel_display.as_str()),
+        ),
+    ]
+}
+
+/// Applies a key read from GSettings, ignoring values that don't parse.
+pub fn apply(config: &mut BatteryConfig, key: &str, value: &str) {
+    // strings are quoted, unsigned integers may carry a `uint32` prefix
+    let value = value.trim();
+    let string = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .map(|v| (!v.is_empty()).then(|| v.to_string()));
+    let unsigned = value.trim_start_matches("uint32 ").parse::<u32>().ok();
+    let boolean = value.parse::<bool>().ok();
+    match key {
+        "docked-battery-protection" => {
+            config.docked_battery_protection = boolean.unwrap_or(config.docked_battery_protection)
+        }
+        "notify-on-unplug" => config.notify_on_unplug = boolean.unwrap_or(config.notify_on_unplug),
+        "notify-on-plug-in" => {
+            config.notify_on_plug_in = boolean.unwrap_or(config.notify_on_plug_in)
+        }
+        "silent-notifications" => {
+            config.silent_notifications = boolean.unwrap_or(config.silent_notifications)
+        }
+        "low-power-density-ui" => {
+            config.low_power_density_ui = boolean.unwrap_or(config.low_power_density_ui)
+        }
+        "battery-icon-theme" => {
+            if let Some(theme) = string {
+                config.battery_icon_theme = theme;
+            }
+        }
+        "profile-on-ac" => {
+            if let Some(profile) = string {
+                config.profile_on_ac = profile;
+            }
+        }
+        "profile-on-battery" => {
+            if let Some(profile) = string {
+                config.profile_on_battery = profile;
+            }
+        }
+        "thermal-mitigation-threshold" => {
+            config.thermal_mitigation_threshold =
+                unsigned.unwrap_or(config.thermal_mitigation_threshold)
+        }
+        "low-battery-percent" => {
+            config.low_battery_percent = unsigned.unwrap_or(config.low_battery_percent)
+        }
+        "critical-battery-percent" => {
+            config.critical_battery_percent = unsigned.unwrap_or(config.critical_battery_percent)
+        }
+        "suspend-on-critical" => {
+            config.suspend_on_critical = boolean.unwrap_or(config.suspend_on_critical)
+        }
+        "panel-display" => {
+            if let Some(display) = string
+                .flatten()
+                .as_deref()
+                .and_then(PanelDisplay::from_name)
+            {
+                config.panel_display = display;
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn gsettings(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("gsettings")
+        .args(args)
+        .output()
+        .await
+        .map_err(|err| format!("Failed to run gsettings: {err}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub async fn export(config: BatteryConfig) -> Result<(), String> {
+    for (key, value) in keys(&config) {
+        gsettings(&["set", SCHEMA, key, &value]).await?;
+    }
+    Ok(())
+}
+
+/// `config` with the values currently held by GSettings.
+pub async fn import(mut config: BatteryConfig) -> Result<BatteryConfig, String> {
+    let values = gsettings(&["list-recursively", SCHEMA]).await?;
+    // <schema> <key> <value>
+    for line in values.lines() {
+        let mut parts = line.splitn(3, ' ').skip(1);
+        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            apply(&mut config, key, value);
+        }
+    }
+    Ok(config)
+}
+
+/// Converts between the COSMIC config and GSettings in one go, for the
+/// `cosmic-applet-battery-gsettings` tool.
+pub async fn convert(direction: Direction) -> Result<(), String> {
+    use cosmic::cosmic_config::{self, CosmicConfigEntry};
+
+    let helper = cosmic_config::Config::new(crate::config::APP_ID, BatteryConfig::VERSION)
+        .map_err(|err| format!("Failed to open the config: {err}"))?;
+    let config = BatteryConfig::get_entry(&helper).unwrap_or_else(|(_, config)| config);
+    match direction {
+        Direction::Export => export(config).await,
+        Direction::Import => import(config)
+            .await?
+            .write_entry(&helper)
+            .map_err(|err| format!("Failed to write the config: {err}")),
+    }
+}
+
+/// Emits each key of the schema that changes, with its new value. Ends
+/// quietly where the schema isn't installed.
+pub fn gsettings_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<(String, String)> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let child = Command::new("gsettings")
+                .args(["monitor", SCHEMA])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn();
+            match child {
+                Ok(mut child) => {
+                    if let Some(stdout) = child.stdout.take() {
+                        let mut lines = BufReader::new(stdout).lines();
+                        // <key>: <value>
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            let Some((key, value)) = line.split_once(": ") else {
+                                continue;
+                            };
+                            if output
+                                .send((key.to_string(), value.to_string()))
+                                .await
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(err) => tracing::warn!("Failed to monitor GSettings: {err}"),
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
@@ -0,0 +1,91 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::backend::device::BatteryDetails;
+use std::time::Duration;
+
+// battery temperature counted as a thermal event
+const HOT_CELSIUS: f64 = 45.0;
+// design life used to scale the cycle count
+const RATED_CYCLES: f64 = 1000.0;
+// uptime at which the uptime component reaches zero
+const MAX_UPTIME: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Counts battery temperature samples above [`HOT_CELSIUS`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThermalHistory {
+    samples: u32,
+    hot: u32,
+}
+
+impl ThermalHistory {
+    pub fn push(&mut self, celsius: f64) {
+        self.samples = self.samples.saturating_add(1);
+        if celsius >= HOT_CELSIUS {
+            self.hot = self.hot.saturating_add(1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    Good,
+    Fair,
+    Poor,
+}
+
+/// Composite 0–100 score; each component is also 0–100.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthScore {
+    pub battery: f64,
+    pub thermal: f64,
+    pub uptime: f64,
+}
+
+impl HealthScore {
+    pub fn new(
+        battery: &BatteryDetails,
+        thermal: &ThermalHistory,
+        uptime: Duration,
+    ) -> Option<Self> {
+        let capacity = battery.capacity?.clamp(0.0, 100.0);
+        let battery = match battery.charge_cycles {
+            Some(cycles) => {
+                let cycles = (1.0 - f64::from(cycles) / RATED_CYCLES).clamp(0.0, 1.0) * 100.0;
+                capacity * 0.8 + cycles * 0.2
+            }
+            None => capacity,
+        };
+        let thermal = if thermal.samples == 0 {
+            100.0
+        } else {
+            (1.0 - f64::from(thermal.hot) / f64::from(thermal.samples)) * 100.0
+        };
+        let uptime =
+            (1.0 - uptime.as_secs_f64() / MAX_UPTIME.as_secs_f64()).clamp(0.0, 1.0) * 100.0;
+
+        Some(Self {
+            battery,
+            thermal,
+            uptime,
+        })
+    }
+
+    pub fn total(&self) -> f64 {
+        self.battery * 0.5 + self.thermal * 0.3 + self.uptime * 0.2
+    }
+
+    pub fn rating(&self) -> Rating {
+        match self.total() {
+            score if score >= 80.0 => Rating::Good,
+            score if score >= 60.0 => Rating::Fair,
+            _ => Rating::Poor,
+        }
+    }
+}
+
+pub async fn uptime() -> Option<Duration> {
+    let contents = tokio::fs::read_to_string("/proc/uptime").await.ok()?;
+    let secs: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Some(Duration::from_secs_f64(secs))
+}
@@ -0,0 +1,255 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::backend::{Power, upower_device::BatteryState};
+use chrono::{DateTime, Local, SecondsFormat, TimeDelta};
+use std::{collections::VecDeque, fmt::Write, path::PathBuf};
+
+// one day of samples at UPower's usual update rate
+const MAX_SAMPLES: usize = 2880;
+// samples used to judge how steady the power draw is
+const VARIATION_SAMPLES: usize = 10;
+// estimated charger efficiency, for energy drawn from the wall
+const CHARGER_EFFICIENCY: f64 = 0.85;
+// discharge history needed before judging the power draw
+const MIN_DRAIN_MINUTES: u64 = 60;
+// standard deviations above the typical power draw counted as unusual
+const DRAIN_OUTLIER_SIGMA: f64 = 2.0;
+// keeps a very steady power draw from flagging small changes
+const MIN_DRAIN_EXCESS_WATTS: f64 = 1.0;
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub time: DateTime<Local>,
+    pub percent: f64,
+    pub watts: f64,
+    pub state: BatteryState,
+    pub profile: Power,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    samples: VecDeque<Sample>,
+}
+
+impl History {
+    pub fn push(&mut self, sample: Sample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Adds samples recorded before the applet started, oldest first, ahead
+    /// of the ones it took itself.
+    pub fn seed(&mut self, samples: impl IntoIterator<Item = Sample>) {
+        let first = self.samples.front().map(|sample| sample.time);
+        let older: Vec<Sample> = samples
+            .into_iter()
+            .filter(|sample| first.is_none_or(|first| sample.time < first))
+            .collect();
+        let room = MAX_SAMPLES - self.samples.len();
+        for sample in older.into_iter().rev().take(room) {
+            self.samples.push_front(sample);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Coefficient of variation of the most recent discharging power samples,
+    /// or `None` without enough samples.
+    pub fn power_variation(&self) -> Option<f64> {
+        let watts: Vec<f64> = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|sample| sample.state == BatteryState::Discharging)
+            .take(VARIATION_SAMPLES)
+            .map(|sample| sample.watts)
+            .collect();
+        if watts.len() < 3 {
+            return None;
+        }
+        let n = watts.len() as f64;
+        let mean = watts.iter().sum::<f64>() / n;
+        if mean <= 0.0 {
+            return None;
+        }
+        let variance = watts.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / n;
+        Some(variance.sqrt() / mean)
+    }
+
+    /// Charge at `time`, from the latest sample taken by then.
+    pub fn percent_at(&self, time: DateTime<Local>) -> Option<f64> {
+        self.samples
+            .iter()
+            .rev()
+            .find(|sample| sample.time <= time)
+            .map(|sample| sample.percent)
+    }
+
+    /// Mean power of the discharging samples matching `filter`.
+    pub fn mean_watts(&self, filter: impl Fn(&Sample) -> bool) -> Option<f64> {
+        let (sum, count) = self
+            .samples
+            .iter()
+            .filter(|sample| sample.state == BatteryState::Discharging && filter(sample))
+            .fold((0.0, 0), |(sum, count), sample| {
+                (sum + sample.watts, count + 1)
+            });
+        (count > 0).then(|| sum / f64::from(count))
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("timestamp,percent,watts,state,profile\n");
+        for sample in &self.samples {
+            let _ = writeln!(
+                csv,
+                "{},{:.1},{:.2},{},{}",
+                sample.time.to_rfc3339_opts(SecondsFormat::Secs, false),
+                sample.percent,
+                sample.watts,
+                sample.state.as_str(),
+                sample.profile.as_str(),
+            );
+        }
+        csv
+    }
+}
+
+/// Energy accounting from changes in the stored battery energy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnergyTotals {
+    last: Option<f64>,
+    /// Watt-hours drawn from the battery
+    pub used: f64,
+    /// Watt-hours drawn from AC power to charge the battery
+    pub wall: f64,
+}
+
+impl EnergyTotals {
+    pub fn push(&mut self, energy: f64, on_battery: bool) {
+        if energy <= 0.0 {
+            return;
+        }
+        if let Some(last) = self.last.replace(energy) {
+            let delta = last - energy;
+            if delta > 0.0 {
+                self.used += delta;
+            } else if !on_battery {
+                self.wall += -delta / CHARGER_EFFICIENCY;
+            }
+        }
+    }
+}
+
+/// Running mean and variance, by Welford's algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Statistics of both sets of values together.
+    fn merge(self, other: Self) -> Self {
+        let count = self.count + other.count;
+        if count == 0 {
+            return Self::default();
+        }
+        let delta = other.mean - self.mean;
+        let weight = self.count as f64 * other.count as f64 / count as f64;
+        Self {
+            count,
+            mean: self.mean + delta * other.count as f64 / count as f64,
+            m2: self.m2 + other.m2 + delta * delta * weight,
+        }
+    }
+
+    fn std_dev(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+        (self.m2 / self.count as f64).sqrt()
+    }
+}
+
+/// Typical discharge rate over about the last day, from one sample a minute.
+///
+/// Samples aren't kept. Instead the statistics of the current day are merged
+/// with those of the previous one, which is dropped once the current day is
+/// complete.
+#[derive(Debug, Clone, Default)]
+pub struct DrainBaseline {
+    previous: RunningStats,
+    current: RunningStats,
+    day_start: Option<DateTime<Local>>,
+    last_sample: Option<DateTime<Local>>,
+}
+
+impl DrainBaseline {
+    /// Records the discharge rate at `time`. Unusual rates are left out, so a
+    /// runaway process doesn't become the norm.
+    pub fn push(&mut self, time: DateTime<Local>, watts: f64) {
+        if watts <= 0.0
+            || self
+                .last_sample
+                .is_some_and(|last| time - last < TimeDelta::minutes(1))
+        {
+            return;
+        }
+        self.last_sample = Some(time);
+        let day_start = *self.day_start.get_or_insert(time);
+        if time - day_start >= TimeDelta::days(1) {
+            self.previous = std::mem::take(&mut self.current);
+            self.day_start = Some(time);
+        }
+        if self.unusual(watts).is_none() {
+            self.current.push(watts);
+        }
+    }
+
+    /// The typical discharge rate, if `watts` is well above it.
+    pub fn unusual(&self, watts: f64) -> Option<f64> {
+        let stats = self.previous.merge(self.current);
+        let limit =
+            stats.mean + (DRAIN_OUTLIER_SIGMA * stats.std_dev()).max(MIN_DRAIN_EXCESS_WATTS);
+        (stats.count >= MIN_DRAIN_MINUTES && watts > limit).then_some(stats.mean)
+    }
+}
+
+/// Asks the user where to save `csv`, returning the chosen path, or `None` if
+/// the dialog was cancelled.
+pub async fn export_csv(csv: String, title: String) -> Result<Option<PathBuf>, String> {
+    use cosmic::dialog::file_chooser::{self, save};
+
+    let name = format!("battery-{}.csv", Local::now().format("%Y-%m-%d"));
+    let response = match save::Dialog::new()
+        .title(title)
+        .current_name(name)
+        .save_file()
+        .await
+    {
+        Ok(response) => response,
+        Err(file_chooser::Error::Cancelled) => return Ok(None),
+        Err(why) => return Err(why.to_string()),
+    };
+
+    let Some(path) = response.url().and_then(|url| url.to_file_path().ok()) else {
+        return Err("Selected location is not a local file".to_string());
+    };
+    tokio::fs::write(&path, csv)
+        .await
+        .map_err(|why| format!("Failed to write {}: {why}", path.display()))?;
+    Ok(Some(path))
+}
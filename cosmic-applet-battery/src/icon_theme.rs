@@ -0,0 +1,99 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Battery icons from an icon theme other than the system one.
+
+use std::path::{Path, PathBuf};
+
+// icon a theme must provide to be offered for battery icons
+const PROBE_ICON: &str = "battery-full-symbolic";
+
+fn icon_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        dirs.push(home.join(".icons"));
+    }
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+    dirs.extend(data_home.map(|dir| dir.join("icons")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(data_dirs.split(':').map(|dir| Path::new(dir).join("icons")));
+    dirs
+}
+
+/// Orders directories so icons closest to `size` come first. Scalable icons
+/// and context directories rank best; smaller bitmaps only after larger ones,
+/// since scaling those up pixelates.
+fn size_rank(dir: &Path, size: u16) -> u32 {
+    let name = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    // `48x48`, `48x48@2` or `48`
+    match name.split('x').next().and_then(|n| n.parse::<u16>().ok()) {
+        None => 0,
+        Some(n) if n >= size => u32::from(n - size) + 1,
+        Some(n) => 1000 + u32::from(size - n),
+    }
+}
+
+// themes lay icons out as `size/context/name` or `context/size/name`
+fn find_in(dir: &Path, name: &str, size: u16, depth: u8) -> Option<PathBuf> {
+    for ext in ["svg", "png"] {
+        let path = dir.join(format!("{name}.{ext}"));
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    if depth == 0 {
+        return None;
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    entries.sort_by_key(|path| size_rank(path, size));
+    entries
+        .iter()
+        .find_map(|path| find_in(path, name, size, depth - 1))
+}
+
+/// Finds `name` in the icon theme `theme`, at the size closest to `size`
+/// pixels.
+pub fn lookup(theme: &str, name: &str, size: u16) -> Option<PathBuf> {
+    icon_dirs()
+        .into_iter()
+        .map(|dir| dir.join(theme))
+        .filter(|dir| dir.is_dir())
+        .find_map(|dir| find_in(&dir, name, size, 2))
+}
+
+/// Installed icon themes providing battery icons, sorted by name.
+pub async fn battery_themes() -> Vec<String> {
+    tokio::task::spawn_blocking(|| {
+        let mut themes: Vec<String> = icon_dirs()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        themes.sort();
+        themes.dedup();
+        themes.retain(|theme| lookup(theme, PROBE_ICON, 16).is_some());
+        themes
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// The freedesktop battery icon name for the given state.
+pub fn battery_icon_name(percent: f64, charging: bool) -> String {
+    let level = ((percent / 10.0).round() * 10.0).clamp(0.0, 100.0) as u8;
+    let charging = if charging { "-charging" } else { "" };
+    format!("battery-level-{level}{charging}-symbolic")
+}
@@ -0,0 +1,40 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Keeps a single applet instance per panel and output.
+//!
+//! Each panel runs its own applet process, so the instance is identified by
+//! the panel and output it was started for. A second process for the same
+//! pair, e.g. one started from a desktop entry, finds the name taken.
+
+use zbus::{Connection, fdo::RequestNameFlags, fdo::RequestNameReply};
+
+const NAME_PREFIX: &str = "com.system76.CosmicAppletBattery.Instance";
+
+// bus name elements only allow `[A-Za-z0-9_-]` and can't start with a digit
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn bus_name() -> String {
+    let panel = std::env::var("COSMIC_PANEL_NAME").unwrap_or_default();
+    let output = std::env::var("COSMIC_PANEL_OUTPUT").unwrap_or_default();
+    format!("{NAME_PREFIX}._{}._{}", sanitize(&panel), sanitize(&output))
+}
+
+/// Claims this instance's bus name, returning `false` if another process
+/// already holds it.
+pub async fn claim(conn: Connection) -> zbus::Result<bool> {
+    let name = bus_name();
+    let reply = conn
+        .request_name_with_flags(name.as_str(), RequestNameFlags::DoNotQueue.into())
+        .await;
+    match reply {
+        Ok(RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner) => Ok(true),
+        Ok(_) | Err(zbus::Error::NameTaken) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
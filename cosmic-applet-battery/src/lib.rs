@@ -3,9 +3,30 @@
 
 mod app;
 mod backend;
+mod battery_ring;
+mod battery_widget;
+mod chemistry;
 mod config;
 mod dgpu;
+mod discharge_chart;
+mod display_server;
+#[cfg(feature = "energy-goals")]
+mod energy_goal;
+mod format;
+mod gesture_area;
+pub mod gsettings;
+mod health;
+mod history;
+mod icon_theme;
+mod instance;
 mod localize;
+mod night_light;
+mod notifications;
+mod schedule;
+mod similar_hardware;
+mod smart_sleep;
+mod state_machine;
+mod status_notifier;
 
 use localize::localize;
 
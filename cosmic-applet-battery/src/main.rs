@@ -4,7 +4,14 @@
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() -> cosmic::iced::Result {
-    tracing_subscriber::fmt::init();
+    // property changes, brightness writes and D-Bus errors, for bug reports
+    if std::env::args().skip(1).any(|arg| arg == "--debug") {
+        tracing_subscriber::fmt()
+            .with_env_filter("warn,cosmic_applet_battery=debug")
+            .init();
+    } else {
+        tracing_subscriber::fmt::init();
+    }
     let _ = tracing_log::LogTracer::init();
 
     tracing::info!("Starting battery applet with version {VERSION}");
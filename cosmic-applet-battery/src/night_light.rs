@@ -0,0 +1,124 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The sunset-to-sunrise schedule of the automatic night light.
+//!
+//! Sunrise and sunset come from the NOAA approximation of the sun's position,
+//! which is within a few minutes away from the poles. Without a location the
+//! evening runs between fixed hours instead.
+
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use std::f64::consts::PI;
+
+// the evening without a location
+const FALLBACK_SUNSET_HOUR: u32 = 20;
+const FALLBACK_SUNRISE_HOUR: u32 = 7;
+
+// solar zenith at sunrise and sunset, allowing for refraction and the sun's disc
+const ZENITH_DEGREES: f64 = 90.833;
+
+/// Whether `now` falls between sunset and sunrise at `location`, a latitude
+/// and longitude in degrees.
+pub fn is_night(now: DateTime<Local>, location: Option<(f64, f64)>) -> bool {
+    let Some((latitude, longitude)) = location else {
+        let hour = now.hour();
+        return !(FALLBACK_SUNRISE_HOUR..FALLBACK_SUNSET_HOUR).contains(&hour);
+    };
+
+    let now = now.with_timezone(&Utc);
+    let day = f64::from(now.ordinal0());
+    // the fractional year, in radians
+    let gamma = 2.0 * PI / 365.0 * day;
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = ZENITH_DEGREES.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle >= 1.0 {
+        // polar night
+        return true;
+    }
+    if cos_hour_angle <= -1.0 {
+        // midnight sun
+        return false;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    // minutes past midnight UTC, possibly outside the day
+    let noon = 720.0 - 4.0 * longitude - equation_of_time;
+    let sunrise = noon - 4.0 * hour_angle;
+    let sunset = noon + 4.0 * hour_angle;
+    let minute = f64::from(now.num_seconds_from_midnight()) / 60.0;
+    // compare within the solar day around `now`
+    let minute = sunrise + (minute - sunrise).rem_euclid(1440.0);
+    minute >= sunset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LONDON: (f64, f64) = (51.5074, -0.1278);
+    const TROMSO: (f64, f64) = (69.6496, 18.9560);
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Utc.with_ymd_and_hms(2024, month, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn london_midsummer() {
+        // sunrise about 03:43 UTC, sunset about 20:21 UTC
+        let location = Some(LONDON);
+        assert!(is_night(utc(6, 21, 3, 30), location));
+        assert!(!is_night(utc(6, 21, 4, 0), location));
+        assert!(!is_night(utc(6, 21, 12, 0), location));
+        assert!(!is_night(utc(6, 21, 20, 10), location));
+        assert!(is_night(utc(6, 21, 20, 35), location));
+        assert!(is_night(utc(6, 21, 23, 59), location));
+    }
+
+    #[test]
+    fn london_midwinter() {
+        // sunrise about 08:04 UTC, sunset about 15:54 UTC
+        let location = Some(LONDON);
+        assert!(is_night(utc(12, 21, 7, 50), location));
+        assert!(!is_night(utc(12, 21, 8, 20), location));
+        assert!(!is_night(utc(12, 21, 15, 40), location));
+        assert!(is_night(utc(12, 21, 16, 10), location));
+    }
+
+    #[test]
+    fn polar_day() {
+        let location = Some(TROMSO);
+        assert!(!is_night(utc(6, 21, 0, 0), location));
+        assert!(!is_night(utc(6, 21, 12, 0), location));
+    }
+
+    #[test]
+    fn polar_night() {
+        let location = Some(TROMSO);
+        assert!(is_night(utc(12, 21, 0, 0), location));
+        assert!(is_night(utc(12, 21, 12, 0), location));
+    }
+
+    #[test]
+    fn fallback_hours() {
+        let local = |hour| Local.with_ymd_and_hms(2024, 3, 1, hour, 0, 0).unwrap();
+        assert!(is_night(local(FALLBACK_SUNRISE_HOUR - 1), None));
+        assert!(!is_night(local(FALLBACK_SUNRISE_HOUR), None));
+        assert!(!is_night(local(FALLBACK_SUNSET_HOUR - 1), None));
+        assert!(is_night(local(FALLBACK_SUNSET_HOUR), None));
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::{
+    backend::{notifications::NotificationsProxy, upower_device::WarningLevel},
+    fl,
+};
+use futures::StreamExt;
+use std::collections::HashMap;
+use zbus::{Connection, zvariant::Value};
+
+pub const TRIP_CHARGE_FULL: &str = "charge-full";
+pub const TRIP_REMIND_LATER: &str = "remind-later";
+pub const SUGGESTION_ACCEPT: &str = "accept";
+pub const SUGGESTION_DISMISS: &str = "dismiss";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub icon: String,
+    pub urgency: Urgency,
+    /// Keep the notification in the tray until the user dismisses it
+    pub resident: bool,
+    /// Milliseconds, or -1 for the server default
+    pub expire_timeout: i32,
+    /// Buttons, as `(key, label)`
+    pub actions: Vec<(String, String)>,
+}
+
+pub async fn send(conn: Connection, notification: Notification) -> zbus::Result<u32> {
+    let proxy = NotificationsProxy::new(&conn).await?;
+    notify(&proxy, notification).await
+}
+
+/// Sends `notification` and waits for it to close, returning the key of the
+/// action the user picked, if any.
+pub async fn send_with_actions(
+    conn: Connection,
+    notification: Notification,
+) -> zbus::Result<Option<String>> {
+    let proxy = NotificationsProxy::new(&conn).await?;
+    // subscribe first, so a quick response isn't missed
+    let mut invoked = proxy.receive_action_invoked().await?;
+    let mut closed = proxy.receive_notification_closed().await?;
+    let id = notify(&proxy, notification).await?;
+
+    loop {
+        tokio::select! {
+            Some(signal) = invoked.next() => {
+                let args = signal.args()?;
+                if args.id == id {
+                    return Ok(Some(args.action_key.to_string()));
+                }
+            }
+            Some(signal) = closed.next() => {
+                if signal.args()?.id == id {
+                    return Ok(None);
+                }
+            }
+            else => return Ok(None),
+        }
+    }
+}
+
+async fn notify(proxy: &NotificationsProxy<'_>, notification: Notification) -> zbus::Result<u32> {
+    let actions: Vec<&str> = notification
+        .actions
+        .iter()
+        .flat_map(|(key, label)| [key.as_str(), label.as_str()])
+        .collect();
+    let mut hints = HashMap::new();
+    hints.insert("urgency", Value::U8(notification.urgency as u8));
+    if notification.resident {
+        hints.insert("resident", Value::Bool(true));
+    }
+    proxy
+        .notify(
+            &fl!("battery"),
+            0,
+            &notification.icon,
+            &notification.summary,
+            &notification.body,
+            &actions,
+            hints,
+            notification.expire_timeout,
+        )
+        .await
+}
+
+/// Low battery warnings, in the order they're reached while discharging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LowBattery {
+    Warning,
+    VeryLow,
+    DeepDischarge,
+    Critical,
+}
+
+impl LowBattery {
+    /// The most severe level reached at `percent`, with the configured
+    /// warning and critical thresholds. The levels in between never start
+    /// above the warning.
+    pub fn for_percent(percent: f64, warning: u32, critical: u32) -> Option<Self> {
+        [
+            (critical, Self::Critical),
+            (8, Self::DeepDischarge),
+            (10, Self::VeryLow),
+            (warning, Self::Warning),
+        ]
+        .into_iter()
+        .find(|(threshold, _)| percent <= f64::from((*threshold).min(warning)))
+        .map(|(_, level)| level)
+    }
+
+    /// The level matching UPower's own warning level for the battery, which
+    /// follows the thresholds in UPower.conf.
+    pub fn for_warning_level(level: WarningLevel) -> Option<Self> {
+        match level {
+            WarningLevel::Low => Some(Self::Warning),
+            WarningLevel::Critical | WarningLevel::Action => Some(Self::Critical),
+            WarningLevel::Unknown | WarningLevel::None | WarningLevel::Discharging => None,
+        }
+    }
+
+    /// `time` is the estimated time to empty.
+    pub fn notification(self, percent: f64, time: Option<String>, icon: &str) -> Notification {
+        let percent = format!("{percent:.0}%");
+        let (summary, urgency, resident) = match self {
+            Self::Warning => (fl!("low-battery"), Urgency::Normal, false),
+            Self::VeryLow => (fl!("very-low-battery"), Urgency::Critical, false),
+            Self::DeepDischarge => (fl!("deep-discharge"), Urgency::Critical, true),
+            Self::Critical => (fl!("critical-battery"), Urgency::Critical, false),
+        };
+        let body = match time {
+            Some(time) => fl!("unplugged-remaining", percent = percent, time = time),
+            None => fl!("battery-remaining", percent = percent),
+        };
+        Notification {
+            summary,
+            body,
+            icon: icon.to_string(),
+            urgency,
+            resident,
+            expire_timeout: -1,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// Sent when AC power is disconnected; `time` is the estimated time to empty.
+pub fn unplugged(percent: f64, time: Option<String>, icon: &str) -> Notification {
+    let percent = format!("{percent:.0}%");
+    let body = match time {
+        Some(time) => fl!("unplugged-remaining", percent = percent, time = time),
+        None => fl!("battery-remaining", percent = percent),
+    };
+    Notification {
+        summary: fl!("unplugged"),
+        body,
+        icon: icon.to_string(),
+        urgency: Urgency::Low,
+        resident: false,
+        expire_timeout: 5000,
+        actions: Vec::new(),
+    }
+}
+
+pub fn plugged_in(percent: f64, icon: &str) -> Notification {
+    Notification {
+        summary: fl!("plugged-in"),
+        body: fl!("plugged-in-charge", percent = format!("{percent:.0}%")),
+        icon: icon.to_string(),
+        urgency: Urgency::Low,
+        resident: false,
+        expire_timeout: 5000,
+        actions: Vec::new(),
+    }
+}
+
+/// Offered when the charging limit is on the day before a trip.
+pub fn charge_limit_suggestion(limit: u8) -> Notification {
+    Notification {
+        summary: fl!("charge-limit-suggestion", limit = limit),
+        body: fl!("charge-limit-suggestion-desc"),
+        icon: "battery-good-charging-symbolic".to_string(),
+        urgency: Urgency::Low,
+        resident: false,
+        expire_timeout: -1,
+        actions: vec![
+            (
+                SUGGESTION_ACCEPT.to_string(),
+                fl!("charge-limit-suggestion-accept"),
+            ),
+            (
+                SUGGESTION_DISMISS.to_string(),
+                fl!("charge-limit-suggestion-dismiss"),
+            ),
+        ],
+    }
+}
+
+pub fn thermal_mitigation() -> Notification {
+    Notification {
+        summary: fl!("thermal-mitigation"),
+        body: fl!("thermal-mitigation-desc"),
+        icon: "power-profile-power-saver-symbolic".to_string(),
+        urgency: Urgency::Normal,
+        resident: false,
+        expire_timeout: -1,
+        actions: Vec::new(),
+    }
+}
+
+#[cfg(feature = "energy-goals")]
+pub fn energy_goal_nearing(used: f64, budget: u32) -> Notification {
+    Notification {
+        summary: fl!("energy-goal-nearing"),
+        body: fl!(
+            "energy-goal-nearing-desc",
+            used = format!("{used:.0}"),
+            budget = budget
+        ),
+        icon: "battery-caution-symbolic".to_string(),
+        urgency: Urgency::Normal,
+        resident: false,
+        expire_timeout: -1,
+        actions: Vec::new(),
+    }
+}
+
+pub fn trip_reminder(limit: u8) -> Notification {
+    Notification {
+        summary: fl!("trip-reminder"),
+        body: fl!("trip-reminder-desc", percent = limit),
+        icon: "battery-full-charging-symbolic".to_string(),
+        urgency: Urgency::Normal,
+        resident: false,
+        expire_timeout: -1,
+        actions: vec![
+            (TRIP_CHARGE_FULL.to_string(), fl!("trip-charge-full")),
+            (TRIP_REMIND_LATER.to_string(), fl!("trip-remind-later")),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_in_order() {
+        let cases = [
+            (50.0, None),
+            (20.0, Some(LowBattery::Warning)),
+            (11.0, Some(LowBattery::Warning)),
+            (10.0, Some(LowBattery::VeryLow)),
+            (9.0, Some(LowBattery::VeryLow)),
+            (8.0, Some(LowBattery::DeepDischarge)),
+            (6.0, Some(LowBattery::DeepDischarge)),
+            (5.0, Some(LowBattery::Critical)),
+            (0.0, Some(LowBattery::Critical)),
+        ];
+        for (percent, expected) in cases {
+            assert_eq!(
+                LowBattery::for_percent(percent, 20, 5),
+                expected,
+                "{percent}"
+            );
+        }
+    }
+
+    #[test]
+    fn fixed_levels_capped_at_warning() {
+        // a warning below 10% moves the fixed levels down with it
+        assert_eq!(LowBattery::for_percent(9.0, 7, 3), None);
+        assert_eq!(
+            LowBattery::for_percent(7.0, 7, 3),
+            Some(LowBattery::DeepDischarge)
+        );
+        assert_eq!(
+            LowBattery::for_percent(3.0, 7, 3),
+            Some(LowBattery::Critical)
+        );
+    }
+
+    #[test]
+    fn critical_above_warning() {
+        // the critical level takes over from the warning
+        assert_eq!(LowBattery::for_percent(25.0, 20, 30), None);
+        assert_eq!(
+            LowBattery::for_percent(20.0, 20, 30),
+            Some(LowBattery::Critical)
+        );
+        assert_eq!(
+            LowBattery::for_percent(20.0, 20, 20),
+            Some(LowBattery::Critical)
+        );
+    }
+
+    #[test]
+    fn upower_warning_levels() {
+        let cases = [
+            (WarningLevel::Unknown, None),
+            (WarningLevel::None, None),
+            (WarningLevel::Discharging, None),
+            (WarningLevel::Low, Some(LowBattery::Warning)),
+            (WarningLevel::Critical, Some(LowBattery::Critical)),
+            (WarningLevel::Action, Some(LowBattery::Critical)),
+        ];
+        for (level, expected) in cases {
+            assert_eq!(LowBattery::for_warning_level(level), expected, "{level:?}");
+        }
+    }
+
+    #[test]
+    fn most_severe_of_both() {
+        // UPower warns first when its thresholds are higher
+        let level = LowBattery::for_percent(25.0, 20, 5)
+            .max(LowBattery::for_warning_level(WarningLevel::Low));
+        assert_eq!(level, Some(LowBattery::Warning));
+        // and the configured thresholds win when they're reached first
+        let level = LowBattery::for_percent(4.0, 20, 5)
+            .max(LowBattery::for_warning_level(WarningLevel::Low));
+        assert_eq!(level, Some(LowBattery::Critical));
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use std::{fmt::Debug, hash::Hash, time::Duration};
+
+/// Time between the countdown notification and powering off.
+pub const COUNTDOWN: TimeDelta = TimeDelta::minutes(5);
+
+// how late a tick may be and still start the countdown for a slot
+const GRACE: TimeDelta = TimeDelta::minutes(1);
+
+/// The scheduled shutdown slot reached at `now`, if any.
+pub fn due(schedule: &[(Weekday, NaiveTime)], now: DateTime<Local>) -> Option<NaiveDateTime> {
+    let now = now.naive_local();
+    schedule
+        .iter()
+        .filter(|(weekday, _)| *weekday == now.weekday())
+        .map(|(_, time)| now.date().and_time(*time))
+        .find(|slot| *slot <= now && now < *slot + GRACE)
+}
+
+pub fn tick_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<()> {
+    interval_subscription(id, Duration::from_secs(30))
+}
+
+/// Ticks every `period`, without catching up on ticks missed while busy.
+pub fn interval_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+    period: Duration,
+) -> iced::Subscription<()> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(1, move |mut output| async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                if output.send(()).await.is_err() {
+                    break;
+                }
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
@@ -0,0 +1,38 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Battery life compared with the typical figure for the same laptop model.
+//!
+//! The typical figures are bundled in `data/battery-life.ron`, matched against
+//! the DMI product name, so nothing about the hardware leaves the machine.
+
+use serde::Deserialize;
+use std::time::Duration;
+
+const DATABASE: &str = include_str!("../data/battery-life.ron");
+// readable without root, unlike the raw DMI tables
+const PRODUCT_NAME_PATH: &str = "/sys/class/dmi/id/product_name";
+
+#[derive(Deserialize)]
+struct Entry {
+    product: String,
+    hours: f64,
+}
+
+/// The typical battery life of this model, or `None` if it isn't known.
+pub async fn typical_battery_life() -> Option<Duration> {
+    let product = tokio::fs::read_to_string(PRODUCT_NAME_PATH).await.ok()?;
+    let product = product.trim().to_lowercase();
+    if product.is_empty() {
+        return None;
+    }
+    let entries: Vec<Entry> = ron::from_str(DATABASE)
+        .inspect_err(|err| tracing::error!("Invalid battery life database: {err}"))
+        .ok()?;
+    // the longest match, so "ThinkPad T14s" wins over "ThinkPad T14"
+    entries
+        .into_iter()
+        .filter(|entry| product.contains(&entry.product.to_lowercase()))
+        .max_by_key(|entry| entry.product.len())
+        .map(|entry| Duration::from_secs_f64(entry.hours * 3600.0))
+}
@@ -0,0 +1,63 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Guesses that a long break is coming from how little input there has been
+//! lately.
+//!
+//! The session counts as idle once there has been no input for
+//! [`IDLE_TIMEOUT`]. The share of time spent active is smoothed with an
+//! exponential moving average over about five minutes.
+
+use std::time::{Duration, Instant};
+
+/// No input for this long counts as idle
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time to cancel by moving the mouse or typing, once the screen dims
+pub const GRACE_PERIOD: Duration = Duration::from_secs(60);
+/// Confidence in a long break needed to start
+pub const MIN_CONFIDENCE: f64 = 0.8;
+/// Screen brightness while waiting to suspend, in percent
+pub const DIM_PERCENT: i32 = 20;
+// time constant of the moving average
+const WINDOW: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct InputActivity {
+    started: Instant,
+    last_sample: Instant,
+    idle: bool,
+    /// Smoothed share of time with input, from 0 to 1
+    average: f64,
+}
+
+impl InputActivity {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            last_sample: now,
+            idle: false,
+            average: 1.0,
+        }
+    }
+
+    /// Accounts for the time since the last sample before changing state.
+    pub fn set_idle(&mut self, idle: bool, now: Instant) {
+        self.sample(now);
+        self.idle = idle;
+    }
+
+    pub fn sample(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_sample).as_secs_f64();
+        self.last_sample = now;
+        let alpha = 1.0 - (-elapsed / WINDOW.as_secs_f64()).exp();
+        let active = if self.idle { 0.0 } else { 1.0 };
+        self.average += alpha * (active - self.average);
+    }
+
+    /// How likely a long break is, from 0 to 1, while idle and once a full
+    /// window of activity was seen.
+    pub fn break_confidence(&self) -> Option<f64> {
+        (self.idle && self.last_sample.duration_since(self.started) >= WINDOW)
+            .then(|| 1.0 - self.average)
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Tracks the power state reported by UPower, ignoring and logging
+//! transitions that shouldn't happen, to help spot bogus state reports and
+//! races between updates.
+
+use crate::backend::upower_device::BatteryState;
+
+// percentage at which a discharging battery is considered critical
+const CRITICAL_PERCENT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PowerState {
+    #[default]
+    Idle,
+    Charging,
+    FullyCharged,
+    Discharging,
+    Critical,
+    Shutdown,
+}
+
+impl PowerState {
+    pub fn new(state: BatteryState, percent: f64) -> Self {
+        match state {
+            BatteryState::Unknown => Self::Idle,
+            BatteryState::Charging | BatteryState::PendingCharge => Self::Charging,
+            BatteryState::FullyCharged => Self::FullyCharged,
+            BatteryState::Discharging | BatteryState::PendingDischarge
+                if percent <= CRITICAL_PERCENT =>
+            {
+                Self::Critical
+            }
+            BatteryState::Discharging | BatteryState::PendingDischarge => Self::Discharging,
+            BatteryState::Empty => Self::Shutdown,
+        }
+    }
+
+    fn can_become(self, next: Self) -> bool {
+        use PowerState::*;
+        match (self, next) {
+            // UPower may lose track of the state at any point
+            (Idle, _) | (_, Idle) => true,
+            (Charging, FullyCharged | Discharging) => true,
+            (FullyCharged, Discharging) => true,
+            (Discharging, Charging | FullyCharged | Critical) => true,
+            (Critical, Charging | Shutdown) => true,
+            (Shutdown, Charging) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppStateMachine {
+    state: PowerState,
+}
+
+impl AppStateMachine {
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+
+    /// Moves to `next` if the current state can become it, returning whether
+    /// it did. Reporting the current state again isn't a transition.
+    pub fn transition(&mut self, next: PowerState) -> bool {
+        let prev = self.state;
+        if prev == next {
+            return false;
+        }
+        if !prev.can_become(next) {
+            tracing::warn!("Unexpected power state transition {prev:?} → {next:?}");
+            return false;
+        }
+        tracing::debug!("Power state {prev:?} → {next:?}");
+        self.state = next;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AppStateMachine,
+        PowerState::{self, *},
+    };
+
+    const ALL: [PowerState; 6] = [
+        Idle,
+        Charging,
+        FullyCharged,
+        Discharging,
+        Critical,
+        Shutdown,
+    ];
+
+    const ALLOWED: [(PowerState, PowerState); 8] = [
+        (Charging, FullyCharged),
+        (Charging, Discharging),
+        (FullyCharged, Discharging),
+        (Discharging, Charging),
+        (Discharging, FullyCharged),
+        (Discharging, Critical),
+        (Critical, Charging),
+        (Critical, Shutdown),
+    ];
+
+    fn machine(state: PowerState) -> AppStateMachine {
+        AppStateMachine { state }
+    }
+
+    fn allowed(prev: PowerState, next: PowerState) -> bool {
+        prev == Idle
+            || next == Idle
+            || (prev, next) == (Shutdown, Charging)
+            || ALLOWED.contains(&(prev, next))
+    }
+
+    #[test]
+    fn allowed_transitions_move() {
+        for prev in ALL {
+            for next in ALL.into_iter().filter(|next| *next != prev) {
+                if allowed(prev, next) {
+                    let mut machine = machine(prev);
+                    assert!(machine.transition(next), "{prev:?} → {next:?}");
+                    assert_eq!(machine.state(), next, "{prev:?} → {next:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn disallowed_transitions_stay() {
+        for prev in ALL {
+            for next in ALL.into_iter().filter(|next| *next != prev) {
+                if !allowed(prev, next) {
+                    let mut machine = machine(prev);
+                    assert!(!machine.transition(next), "{prev:?} → {next:?}");
+                    assert_eq!(machine.state(), prev, "{prev:?} → {next:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn repeated_state_stays() {
+        for state in ALL {
+            let mut machine = machine(state);
+            assert!(!machine.transition(state), "{state:?}");
+            assert_eq!(machine.state(), state);
+        }
+    }
+
+    #[test]
+    fn charging_needs_discharging_after_full() {
+        let mut machine = machine(FullyCharged);
+        assert!(!machine.transition(Charging));
+        assert!(machine.transition(Discharging));
+        assert!(machine.transition(Charging));
+        assert_eq!(machine.state(), Charging);
+    }
+}
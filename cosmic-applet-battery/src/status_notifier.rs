@@ -0,0 +1,177 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A `StatusNotifierItem`, for showing the battery in a system tray.
+//!
+//! The item only mirrors the panel icon; activating it opens the same popup
+//! the panel button would.
+
+use cosmic::{
+    iced::{self, Subscription, futures::SinkExt},
+    iced_futures::stream,
+};
+use std::{fmt::Debug, hash::Hash};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use zbus::{interface, object_server::SignalEmitter, proxy};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+
+#[proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher"
+)]
+trait StatusNotifierWatcher {
+    /// RegisterStatusNotifierItem method
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+/// Pixmaps are left empty, so hosts look the icon up by name.
+type ToolTip = (String, Vec<(i32, i32, Vec<u8>)>, String, String);
+
+struct Item {
+    icon_name: String,
+    tooltip: String,
+    activated: UnboundedSender<()>,
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    fn activate(&self, _x: i32, _y: i32) {
+        _ = self.activated.send(());
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        _ = self.activated.send(());
+    }
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "Hardware"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "cosmic-applet-battery"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> String {
+        crate::fl!("battery")
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        &self.icon_name
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> ToolTip {
+        (
+            self.icon_name.clone(),
+            Vec::new(),
+            crate::fl!("battery"),
+            self.tooltip.clone(),
+        )
+    }
+
+    #[zbus(signal)]
+    async fn new_icon(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone)]
+pub enum StatusNotifierRequest {
+    Update { icon_name: String, tooltip: String },
+}
+
+#[derive(Debug, Clone)]
+pub enum StatusNotifierEvent {
+    Init(UnboundedSender<StatusNotifierRequest>),
+    Activate,
+}
+
+pub fn status_notifier_subscription<I: 'static + Hash + Copy + Send + Sync + Debug>(
+    id: I,
+) -> iced::Subscription<StatusNotifierEvent> {
+    Subscription::run_with_id(
+        id,
+        stream::channel(10, move |mut output| async move {
+            if let Err(err) = serve(&mut output).await {
+                tracing::error!("StatusNotifierItem failed: {err}");
+            }
+
+            iced::futures::future::pending().await
+        }),
+    )
+}
+
+async fn serve(
+    output: &mut futures::channel::mpsc::Sender<StatusNotifierEvent>,
+) -> zbus::Result<()> {
+    let (activated, mut activate_rx) = unbounded_channel();
+    let (tx, mut rx) = unbounded_channel();
+    // the spec's naming, made unique per process
+    let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    let item = Item {
+        icon_name: "battery-symbolic".to_string(),
+        tooltip: String::new(),
+        activated,
+    };
+    let conn = zbus::connection::Builder::session()?
+        .name(name.as_str())?
+        .serve_at(ITEM_PATH, item)?
+        .build()
+        .await?;
+    StatusNotifierWatcherProxy::new(&conn)
+        .await?
+        .register_status_notifier_item(&name)
+        .await?;
+    if output.send(StatusNotifierEvent::Init(tx)).await.is_err() {
+        return Ok(());
+    }
+
+    let iface = conn.object_server().interface::<_, Item>(ITEM_PATH).await?;
+    loop {
+        tokio::select! {
+            Some(()) = activate_rx.recv() => {
+                if output.send(StatusNotifierEvent::Activate).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Some(request) = rx.recv() => match request {
+                StatusNotifierRequest::Update { icon_name, tooltip } => {
+                    let mut item = iface.get_mut().await;
+                    let icon_changed = item.icon_name != icon_name;
+                    let tooltip_changed = item.tooltip != tooltip;
+                    item.icon_name = icon_name;
+                    item.tooltip = tooltip;
+                    drop(item);
+                    if icon_changed {
+                        Item::new_icon(iface.signal_emitter()).await?;
+                    }
+                    if icon_changed || tooltip_changed {
+                        Item::new_tool_tip(iface.signal_emitter()).await?;
+                    }
+                }
+            },
+            else => return Ok(()),
+        }
+    }
+}
@@ -160,7 +160,7 @@ impl cosmic::Application for CosmicBluetoothApplet {
                     err_msg,
                 } => {
                     if let Some(err_msg) = err_msg {
-                        eprintln!("bluetooth request error: {}", err_msg);
+                        tracing::error!("bluetooth request error: {}", err_msg);
                     }
                     if self.bluer_state.bluetooth_enabled != state.bluetooth_enabled {
                         self.timeline
@@ -197,7 +197,7 @@ impl cosmic::Application for CosmicBluetoothApplet {
                 }
                 BluerEvent::Finished => {
                     // TODO should this exit with an error causing a restart?
-                    eprintln!("bluetooth subscription finished. exiting...");
+                    tracing::error!("bluetooth subscription finished. exiting...");
                     std::process::exit(0);
                 }
                 // TODO handle agent events
@@ -0,0 +1,15 @@
+graphics = Graphics
+graphics-mode = Graphics Mode
+integrated = Integrated
+integrated-desc = Uses only the integrated GPU, for the longest battery life
+hybrid = Hybrid
+hybrid-desc = Runs on the integrated GPU, with the NVIDIA GPU on demand
+nvidia = NVIDIA
+nvidia-desc = Runs everything on the NVIDIA GPU
+compute = Compute
+compute-desc = Keeps the NVIDIA GPU for computation, displays use the integrated GPU
+requires-restart = Requires restart
+switching = Switching graphics mode...
+restart-to-switch = Restart to switch to { $mode } graphics.
+restart = Restart
+not-switchable = Graphics switching is unavailable on this system
@@ -0,0 +1,67 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Graphics switching through `system76-power`. The graphics methods are part
+//! of the daemon's main `com.system76.PowerDaemon` interface.
+
+use zbus::{Connection, proxy};
+
+#[proxy(
+    interface = "com.system76.PowerDaemon",
+    default_service = "com.system76.PowerDaemon",
+    default_path = "/com/system76/PowerDaemon"
+)]
+pub trait Graphics {
+    /// GetGraphics method
+    fn get_graphics(&self) -> zbus::Result<String>;
+
+    /// GetSwitchable method
+    fn get_switchable(&self) -> zbus::Result<bool>;
+
+    /// SetGraphics method
+    fn set_graphics(&self, vendor: &str) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Integrated,
+    Hybrid,
+    Nvidia,
+    Compute,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [Mode::Integrated, Mode::Hybrid, Mode::Nvidia, Mode::Compute];
+
+    /// The vendor name `system76-power` uses for the mode.
+    pub fn vendor(self) -> &'static str {
+        match self {
+            Mode::Integrated => "integrated",
+            Mode::Hybrid => "hybrid",
+            Mode::Nvidia => "nvidia",
+            Mode::Compute => "compute",
+        }
+    }
+
+    pub fn from_vendor(vendor: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.vendor() == vendor)
+    }
+}
+
+/// The configured mode, or `None` where the system can't switch graphics.
+pub async fn graphics() -> zbus::Result<Option<Mode>> {
+    let connection = Connection::system().await?;
+    let proxy = GraphicsProxy::new(&connection).await?;
+    if !proxy.get_switchable().await? {
+        return Ok(None);
+    }
+    Ok(Mode::from_vendor(&proxy.get_graphics().await?))
+}
+
+/// Configures `mode`, which takes effect on the next boot. This can take a
+/// while, as the daemon regenerates the initramfs.
+pub async fn set_graphics(mode: Mode) -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let proxy = GraphicsProxy::new(&connection).await?;
+    proxy.set_graphics(mode.vendor()).await
+}
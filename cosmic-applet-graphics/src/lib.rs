@@ -0,0 +1,254 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::{
+    Element, Task, app,
+    applet::{menu_button, padded_control},
+    cosmic_theme::Spacing,
+    iced::{
+        Alignment, Length,
+        platform_specific::shell::commands::popup::{destroy_popup, get_popup},
+        widget::{self, column, row},
+        window,
+    },
+    surface, theme,
+    widget::{Space, container, divider, icon, text},
+};
+use logind_zbus::manager::ManagerProxy;
+use zbus::Connection;
+
+mod graphics;
+mod localize;
+
+use crate::graphics::Mode;
+
+const ICON: &str = "com.system76.CosmicAppletGraphics-symbolic";
+
+pub fn run() -> cosmic::iced::Result {
+    localize::localize();
+
+    cosmic::applet::run::<Graphics>(())
+}
+
+#[derive(Default)]
+struct Graphics {
+    core: cosmic::app::Core,
+    popup: Option<window::Id>,
+    /// The mode when the applet started, which is taken as the running one
+    /// since switching only takes effect on reboot
+    booted: Option<Mode>,
+    /// The mode the next boot will use
+    configured: Option<Mode>,
+    /// A switch in progress
+    switching: Option<Mode>,
+}
+
+impl Graphics {
+    fn restart_required(&self) -> bool {
+        self.configured.is_some() && self.configured != self.booted
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    TogglePopup,
+    Graphics(Result<Option<Mode>, zbus::Error>),
+    Select(Mode),
+    Switched(Mode, Result<(), zbus::Error>),
+    Restart,
+    Zbus(Result<(), zbus::Error>),
+    Closed(window::Id),
+    Surface(surface::Action),
+}
+
+impl cosmic::Application for Graphics {
+    type Executor = cosmic::SingleThreadExecutor;
+    type Flags = ();
+    type Message = Message;
+    const APP_ID: &'static str = "com.system76.CosmicAppletGraphics";
+
+    fn core(&self) -> &cosmic::app::Core {
+        &self.core
+    }
+
+    fn core_mut(&mut self) -> &mut cosmic::app::Core {
+        &mut self.core
+    }
+
+    fn init(core: cosmic::app::Core, _flags: ()) -> (Self, app::Task<Message>) {
+        (
+            Self {
+                core,
+                ..Default::default()
+            },
+            Task::perform(graphics::graphics(), |result| {
+                cosmic::action::app(Message::Graphics(result))
+            }),
+        )
+    }
+
+    fn on_close_requested(&self, id: window::Id) -> Option<Message> {
+        Some(Message::Closed(id))
+    }
+
+    fn update(&mut self, message: Message) -> app::Task<Message> {
+        match message {
+            Message::TogglePopup => {
+                if let Some(p) = self.popup.take() {
+                    destroy_popup(p)
+                } else {
+                    let new_id = window::Id::unique();
+                    self.popup.replace(new_id);
+
+                    let popup_settings = self.core.applet.get_popup_settings(
+                        self.core.main_window_id().unwrap(),
+                        new_id,
+                        None,
+                        None,
+                        None,
+                    );
+
+                    get_popup(popup_settings)
+                }
+            }
+            Message::Graphics(result) => {
+                match result {
+                    Ok(mode) => {
+                        self.booted = mode;
+                        self.configured = mode;
+                    }
+                    Err(err) => tracing::error!("Failed to get the graphics mode: {err}"),
+                }
+                Task::none()
+            }
+            Message::Select(mode) => {
+                self.switching = Some(mode);
+                Task::perform(graphics::set_graphics(mode), move |result| {
+                    cosmic::action::app(Message::Switched(mode, result))
+                })
+            }
+            Message::Switched(mode, result) => {
+                self.switching = None;
+                match result {
+                    Ok(()) => self.configured = Some(mode),
+                    Err(err) => {
+                        tracing::error!("Failed to switch to {} graphics: {err}", mode.vendor())
+                    }
+                }
+                Task::none()
+            }
+            Message::Restart => Task::perform(restart(), |result| {
+                cosmic::action::app(Message::Zbus(result))
+            }),
+            Message::Zbus(result) => {
+                if let Err(err) = result {
+                    tracing::error!("Failed to restart: {err}");
+                }
+                Task::none()
+            }
+            Message::Closed(id) => {
+                if self.popup == Some(id) {
+                    self.popup = None;
+                }
+                Task::none()
+            }
+            Message::Surface(a) => {
+                cosmic::task::message(cosmic::Action::Cosmic(cosmic::app::Action::Surface(a)))
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        self.core
+            .applet
+            .icon_button(ICON)
+            .on_press_down(Message::TogglePopup)
+            .into()
+    }
+
+    fn view_window(&self, id: window::Id) -> Element<Message> {
+        let Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        if !matches!(self.popup, Some(p) if p == id) {
+            return widget::text("").into();
+        }
+
+        let mut content = column![padded_control(text::heading(fl!("graphics-mode")))]
+            .align_x(Alignment::Start)
+            .padding([8, 0]);
+
+        if self.configured.is_none() {
+            content = content.push(padded_control(text::body(fl!("not-switchable"))));
+            return self.core.applet.popup_container(content).into();
+        }
+
+        for mode in Mode::ALL {
+            let (label, description) = mode_text(mode);
+            let selected = self.switching.or(self.configured) == Some(mode);
+            content = content.push(
+                menu_button(
+                    row![
+                        column![text::body(label), text::caption(description)]
+                            .push_maybe(
+                                (Some(mode) != self.booted)
+                                    .then(|| text::caption(fl!("requires-restart")))
+                            )
+                            .width(Length::Fill),
+                        if selected {
+                            container(
+                                icon::from_name("emblem-ok-symbolic")
+                                    .size(12)
+                                    .symbolic(true),
+                            )
+                        } else {
+                            container(Space::with_width(1.0))
+                        }
+                    ]
+                    .align_y(Alignment::Center)
+                    .spacing(space_xxs),
+                )
+                .on_press_maybe(
+                    (self.switching.is_none() && !selected).then_some(Message::Select(mode)),
+                ),
+            );
+        }
+
+        if self.switching.is_some() {
+            content = content
+                .push(padded_control(divider::horizontal::default()).padding([space_xxs, space_s]))
+                .push(padded_control(text::body(fl!("switching"))));
+        } else if let Some(mode) = self.configured.filter(|_| self.restart_required()) {
+            content = content
+                .push(padded_control(divider::horizontal::default()).padding([space_xxs, space_s]))
+                .push(padded_control(text::body(fl!(
+                    "restart-to-switch",
+                    mode = mode_text(mode).0
+                ))))
+                .push(menu_button(text::body(fl!("restart"))).on_press(Message::Restart));
+        }
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        Some(cosmic::applet::style())
+    }
+}
+
+/// The label and description of `mode`.
+fn mode_text(mode: Mode) -> (String, String) {
+    match mode {
+        Mode::Integrated => (fl!("integrated"), fl!("integrated-desc")),
+        Mode::Hybrid => (fl!("hybrid"), fl!("hybrid-desc")),
+        Mode::Nvidia => (fl!("nvidia"), fl!("nvidia-desc")),
+        Mode::Compute => (fl!("compute"), fl!("compute-desc")),
+    }
+}
+
+async fn restart() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.reboot(true).await
+}
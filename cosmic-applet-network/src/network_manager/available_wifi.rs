@@ -23,7 +23,7 @@ pub async fn handle_wireless_device(
     let mut scan_changed = device.receive_last_scan_changed().await;
     if let Some(t) = scan_changed.next().await {
         if let Ok(-1) = t.get().await {
-            eprintln!("scan errored");
+            tracing::error!("scan errored");
             return Ok(Default::default());
         }
     }
@@ -163,7 +163,7 @@ impl cosmic::Application for Power {
             }
             Message::Zbus(result) => {
                 if let Err(e) = result {
-                    eprintln!("cosmic-applet-power ERROR: '{}'", e);
+                    tracing::error!("D-Bus call failed: {e}");
                 }
                 Task::none()
             }
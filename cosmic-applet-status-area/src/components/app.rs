@@ -211,7 +211,7 @@ impl cosmic::Application for App {
                     self.resize_window()
                 }
                 status_notifier_watcher::Event::Error(err) => {
-                    eprintln!("Status notifier error: {}", err);
+                    tracing::error!("Status notifier error: {}", err);
                     Task::none()
                 }
             },
@@ -9,7 +9,10 @@ use cosmic::{
     widget::icon,
 };
 
-use crate::subscriptions::status_notifier_item::{IconUpdate, Layout, StatusNotifierItem};
+use crate::subscriptions::{
+    dbusmenu::Layout,
+    status_notifier_item::{IconUpdate, StatusNotifierItem},
+};
 
 #[derive(Clone, Debug)]
 pub enum Msg {
@@ -56,7 +59,7 @@ impl State {
                     Ok(layout) => {
                         self.layout = Some(layout);
                     }
-                    Err(err) => eprintln!("Error getting layout from icon: {}", err),
+                    Err(err) => tracing::error!("Error getting layout from icon: {}", err),
                 }
                 iced::Task::none()
             }
@@ -72,7 +75,7 @@ impl State {
                             .map(|mut i| {
                                 if i.width <= 0 || i.height <= 0 || i.bytes.is_empty() {
                                     // App sent invalid icon data during initialization - show placeholder until NewIcon signal
-                                    eprintln!("Skipping invalid icon: {}x{} with {} bytes, app may still be initializing",
+                                    tracing::warn!("Skipping invalid icon: {}x{} with {} bytes, app may still be initializing",
                                             i.width, i.height, i.bytes.len());
                                     return icon::from_name("dialog-question").symbolic(true).handle();
                                 }
@@ -102,11 +105,16 @@ impl State {
                     return iced::Task::none();
                 };
 
+                let opening_submenu = is_submenu && self.expanded != Some(id);
                 let menu_proxy = self.item.menu_proxy().clone();
                 let item_proxy = self.item.item_proxy().clone();
                 tokio::spawn(async move {
                     let _ = item_proxy.provide_xdg_activation_token(token).await;
                     let _ = menu_proxy.event(id, "clicked", &0.into(), 0).await;
+                    // lets the app fill in the submenu, the layout update follows
+                    if opening_submenu {
+                        let _ = menu_proxy.about_to_show(id).await;
+                    }
                 });
                 if is_submenu {
                     self.expanded = if self.expanded != Some(id) {
@@ -209,13 +217,22 @@ fn layout_view(layout: &Layout, expanded: Option<i32>) -> cosmic::Element<Msg> {
                     .symbolic(true);
                 children.insert(0, icon.into());
             }
-            if i.toggle_state() == Some(1) {
-                let icon = cosmic::widget::icon::from_name("emblem-ok-symbolic")
+            let toggled = i.toggle_state() == Some(1);
+            // radio items show their state either way, so the group reads as one
+            let toggle_icon = match i.toggle_type() {
+                Some("radio") if toggled => Some("radio-checked-symbolic"),
+                Some("radio") => Some("radio-symbolic"),
+                _ if toggled => Some("emblem-ok-symbolic"),
+                _ => None,
+            };
+            if let Some(name) = toggle_icon {
+                let icon = cosmic::widget::icon::from_name(name)
                     .size(14)
                     .symbolic(true);
                 children.push(icon.into());
             }
-            let button = row_button(children).on_press(Msg::Click(i.id(), is_submenu));
+            let button = row_button(children)
+                .on_press_maybe(i.enabled().then_some(Msg::Click(i.id(), is_submenu)));
 
             if is_submenu && is_expanded {
                 Some(
@@ -95,7 +95,7 @@ pub async fn create_service(connection: &zbus::Connection) -> zbus::Result<()> {
 
     let flags = RequestNameFlags::AllowReplacement.into();
     if dbus_proxy.request_name(NAME.as_ref(), flags).await? == RequestNameReply::InQueue {
-        eprintln!("Bus name '{}' already owned", NAME);
+        tracing::warn!("Bus name '{}' already owned", NAME);
     }
 
     let connection = connection.clone();
@@ -111,10 +111,10 @@ pub async fn create_service(connection: &zbus::Connection) -> zbus::Result<()> {
             };
             if args.name.as_ref() == NAME {
                 if args.new_owner.as_ref() == unique_name.as_ref() {
-                    eprintln!("Acquired bus name: {}", NAME);
+                    tracing::info!("Acquired bus name: {}", NAME);
                     have_bus_name = true;
                 } else if have_bus_name {
-                    eprintln!("Lost bus name: {}", NAME);
+                    tracing::info!("Lost bus name: {}", NAME);
                     have_bus_name = false;
                 }
             } else if let BusName::Unique(name) = &args.name {
@@ -150,7 +150,7 @@ pub fn spawn_workspaces(tx: mpsc::Sender<TilingState>) -> SyncSender<AppRequest>
             }
         });
     } else {
-        eprintln!("ENV variable WAYLAND_DISPLAY is missing. Exiting...");
+        tracing::error!("ENV variable WAYLAND_DISPLAY is missing. Exiting...");
         std::process::exit(1);
     }
 
@@ -105,7 +105,7 @@ pub fn spawn_workspaces(tx: mpsc::Sender<Vec<Workspace>>) -> SyncSender<Workspac
             }
         });
     } else {
-        eprintln!("ENV variable WAYLAND_DISPLAY is missing. Exiting...");
+        tracing::error!("ENV variable WAYLAND_DISPLAY is missing. Exiting...");
         std::process::exit(1);
     }
 
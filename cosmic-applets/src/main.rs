@@ -4,9 +4,6 @@
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() -> cosmic::iced::Result {
-    tracing_subscriber::fmt().with_env_filter("warn").init();
-    let _ = tracing_log::LogTracer::init();
-
     let Some(applet) = std::env::args().next() else {
         return Ok(());
     };
@@ -14,6 +11,16 @@ fn main() -> cosmic::iced::Result {
     let start = applet.rfind('/').map(|v| v + 1).unwrap_or(0);
     let cmd = &applet.as_str()[start..];
 
+    // everything the applet itself logs, for bug reports
+    if std::env::args().skip(1).any(|arg| arg == "--debug") {
+        tracing_subscriber::fmt()
+            .with_env_filter(format!("warn,{}=debug", cmd.replace('-', "_")))
+            .init();
+    } else {
+        tracing_subscriber::fmt().with_env_filter("warn").init();
+    }
+    let _ = tracing_log::LogTracer::init();
+
     tracing::info!("Starting `{cmd}` with version {VERSION}");
 
     match cmd {
@@ -22,6 +29,7 @@ fn main() -> cosmic::iced::Result {
         "cosmic-applet-audio" => cosmic_applet_audio::run(),
         "cosmic-applet-battery" => cosmic_applet_battery::run(),
         "cosmic-applet-bluetooth" => cosmic_applet_bluetooth::run(),
+        "cosmic-applet-graphics" => cosmic_applet_graphics::run(),
         "cosmic-applet-minimize" => cosmic_applet_minimize::run(),
         "cosmic-applet-network" => cosmic_applet_network::run(),
         "cosmic-applet-notifications" => cosmic_applet_notifications::run(),